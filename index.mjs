@@ -1,4 +1,13 @@
-import initWasm, { initSync as initWasmSync, transform as wasmTransform } from "./pkg/tsx.js";
+import initWasm, {
+  ImportMap,
+  initSync as initWasmSync,
+  transform as wasmTransform,
+  transformMany as wasmTransformMany,
+  transformManyWithImportMap as wasmTransformManyWithImportMap,
+  transformWithImportMap as wasmTransformWithImportMap,
+} from "./pkg/tsx.js";
+
+export { ImportMap };
 
 export function transform(options) {
   const { filename, code, importMap } = options;
@@ -11,12 +20,42 @@ export function transform(options) {
   if (!(options.code instanceof Uint8Array)) {
     throw new Error("code is required");
   }
+  if (importMap instanceof ImportMap) {
+    const { importMap: _, ...rest } = options;
+    return wasmTransformWithImportMap(rest, importMap);
+  }
   if (importMap !== undefined && !(typeof importMap === "object" && importMap !== null && !Array.isArray(importMap))) {
     throw new Error("invalid importMap");
   }
   return wasmTransform(options);
 }
 
+export function transformMany(inputs, importMap) {
+  if (!Array.isArray(inputs)) {
+    throw new Error("inputs must be an array");
+  }
+  const options = inputs.map(({ filename, code, options }) => ({
+    ...options,
+    filename,
+    code: typeof code === "string" ? new TextEncoder().encode(code) : code,
+  }));
+  if (importMap instanceof ImportMap) {
+    return wasmTransformManyWithImportMap(options, importMap);
+  }
+  if (importMap !== undefined) {
+    if (!(typeof importMap === "object" && importMap !== null && !Array.isArray(importMap))) {
+      throw new Error("invalid importMap");
+    }
+    const im = new ImportMap(importMap);
+    try {
+      return wasmTransformManyWithImportMap(options, im);
+    } finally {
+      im.free();
+    }
+  }
+  return wasmTransformMany(options);
+}
+
 export function initSync(module) {
   return initWasmSync({ module });
 }
@@ -1,12 +1,14 @@
+use crate::error::{DiagnosticMessage, Severity};
 use crate::resolver::Resolver;
 use crate::swc_helpers::*;
+use indexmap::IndexMap;
 use serde::Deserialize;
 use std::cell::RefCell;
 use std::rc::Rc;
-use swc_common::{DUMMY_SP, SyntaxContext};
+use swc_common::{DUMMY_SP, SourceMap, SyntaxContext};
 use swc_ecmascript::ast::*;
 use swc_ecmascript::utils::quote_ident;
-use swc_ecmascript::visit::{Fold, noop_fold_type};
+use swc_ecmascript::visit::{Fold, VisitMut, VisitMutWith, noop_fold_type, noop_visit_mut_type};
 
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -26,7 +28,7 @@ pub struct JsxSourceOptions {
   pub file_name: String,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DevOptions {
   pub hmr: Option<HmrOptions>,
@@ -35,17 +37,6 @@ pub struct DevOptions {
   pub jsx_source: Option<JsxSourceOptions>,
 }
 
-impl Default for DevOptions {
-  fn default() -> Self {
-    DevOptions {
-      hmr: None,
-      refresh: None,
-      prefresh: None,
-      jsx_source: None,
-    }
-  }
-}
-
 pub struct Dev {
   pub resolver: Rc<RefCell<Resolver>>,
   pub options: DevOptions,
@@ -95,13 +86,12 @@ impl Fold for Dev {
 
     if let Some(refresh_options) = self.options.refresh.as_ref().or(self.options.prefresh.as_ref()) {
       for item in &module_items {
-        if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &item {
-          if let Expr::Call(call) = expr.as_ref() {
-            if is_call_expr_by_name(&call, "$RefreshReg$") {
-              refresh = true;
-              break;
-            }
-          }
+        if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = &item
+          && let Expr::Call(call) = expr.as_ref()
+          && is_call_expr_by_name(call, "$RefreshReg$")
+        {
+          refresh = true;
+          break;
         }
       }
       if refresh {
@@ -136,8 +126,8 @@ impl Fold for Dev {
         items.push(window_assign("$RefreshSig$", simple_member_expr("__REFRESH_RUNTIME__", "sign")));
       } else {
         let mut has_react_dom_import = false;
-        for (specifier, _) in &resolver.deps {
-          if specifier.eq("react-dom") || specifier.eq("react-dom/client") {
+        for dep in &resolver.deps {
+          if dep.specifier.eq("react-dom") || dep.specifier.eq("react-dom/client") {
             has_react_dom_import = true;
             break;
           }
@@ -192,3 +182,115 @@ impl Fold for Dev {
     items
   }
 }
+
+/// Replace the `import.meta.env.*` accesses with the literals of the `env` variables,
+/// the built-in `DEV`, `PROD` and `MODE` follow the `dev` option.
+pub struct ImportMetaEnv {
+  pub resolver: Rc<RefCell<Resolver>>,
+  pub source_map: Rc<SourceMap>,
+  /// the variables of the `env` option, the unknown variables are kept as they are if it's not set.
+  pub env: Option<IndexMap<String, serde_json::Value>>,
+  pub dev: bool,
+}
+
+impl ImportMetaEnv {
+  /// The value of the variable, the `env` variables override the built-in ones.
+  fn lookup(&self, key: &str) -> Option<Expr> {
+    if let Some(value) = self.env.as_ref().and_then(|env| env.get(key)) {
+      return Some(json_expr(value));
+    }
+    match key {
+      "DEV" => Some(Expr::Lit(Lit::Bool(self.dev.into()))),
+      "PROD" => Some(Expr::Lit(Lit::Bool((!self.dev).into()))),
+      "MODE" => Some(str_expr(if self.dev { "development" } else { "production" })),
+      _ => None,
+    }
+  }
+
+  /// The `import.meta.env` object with all the variables.
+  fn env_object(&self) -> Expr {
+    let mut props = ["DEV", "PROD", "MODE"]
+      .into_iter()
+      .filter(|key| !self.env.as_ref().is_some_and(|env| env.contains_key(*key)))
+      .map(|key| kv_prop(key, self.lookup(key).unwrap()))
+      .collect::<Vec<_>>();
+    for (key, value) in self.env.iter().flatten() {
+      props.push(kv_prop(key, json_expr(value)));
+    }
+    object_expr(props)
+  }
+}
+
+impl VisitMut for ImportMetaEnv {
+  noop_visit_mut_type!();
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    if let Expr::Member(MemberExpr { span, obj, prop }) = expr
+      && is_import_meta_env(obj)
+    {
+      let key = match prop {
+        MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        MemberProp::Computed(ComputedPropName { expr, .. }) => match expr.as_ref() {
+          Expr::Lit(Lit::Str(s)) => Some(s.value.to_string_lossy().into_owned()),
+          _ => None,
+        },
+        MemberProp::PrivateName(_) => None,
+      };
+      if let Some(key) = key {
+        if let Some(value) = self.lookup(&key) {
+          *expr = value;
+          return;
+        }
+        if self.env.is_some() {
+          let mut resolver = self.resolver.borrow_mut();
+          let warning = DiagnosticMessage::new(
+            format!("Unknown env variable \"import.meta.env.{}\", it's replaced with undefined", key),
+            Severity::Warning,
+            &resolver.filename,
+            Some(*span),
+            &self.source_map,
+          );
+          resolver.warnings.push(warning);
+          *expr = *Expr::undefined(DUMMY_SP);
+          return;
+        }
+      }
+    } else if self.env.is_some() && is_import_meta_env(expr) {
+      *expr = self.env_object();
+      return;
+    }
+    expr.visit_mut_children_with(self);
+  }
+}
+
+/// Check if the expression is `import.meta.env`.
+fn is_import_meta_env(expr: &Expr) -> bool {
+  matches!(
+    expr,
+    Expr::Member(MemberExpr { obj, prop: MemberProp::Ident(prop), .. })
+      if prop.sym == "env" && matches!(obj.as_ref(), Expr::MetaProp(MetaPropExpr { kind: MetaPropKind::ImportMeta, .. }))
+  )
+}
+
+/// Convert a JSON value to the literal expression.
+fn json_expr(value: &serde_json::Value) -> Expr {
+  match value {
+    serde_json::Value::Null => Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+    serde_json::Value::Bool(b) => Expr::Lit(Lit::Bool((*b).into())),
+    serde_json::Value::Number(n) => Expr::Lit(Lit::Num(n.as_f64().unwrap_or_default().into())),
+    serde_json::Value::String(s) => str_expr(s),
+    serde_json::Value::Array(items) => Expr::Array(ArrayLit {
+      span: DUMMY_SP,
+      elems: items
+        .iter()
+        .map(|item| {
+          Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(json_expr(item)),
+          })
+        })
+        .collect(),
+    }),
+    serde_json::Value::Object(obj) => object_expr(obj.iter().map(|(key, value)| kv_prop(key, json_expr(value))).collect()),
+  }
+}
@@ -0,0 +1,140 @@
+use crate::error::{DiagnosticBuffer, DiagnosticMessage, DiagnosticSpan, Position, Severity};
+use crate::resolver::DependencyDescriptor;
+use std::borrow::Cow;
+
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16_BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+/// The decoded source code, the byte offsets in it can be mapped back to the raw input.
+pub struct DecodedSource<'a> {
+  pub code: Cow<'a, str>,
+  bom_len: usize,
+  utf16: bool,
+}
+
+impl DecodedSource<'_> {
+  /// Map a byte offset of the decoded code to the byte offset of the raw input.
+  pub fn raw_offset(&self, offset: u32) -> u32 {
+    let offset = offset as usize;
+    let raw_offset = if self.utf16 {
+      self
+        .code
+        .char_indices()
+        .take_while(|(i, _)| *i < offset)
+        .map(|(_, c)| c.len_utf16() * 2)
+        .sum()
+    } else {
+      offset
+    };
+    (self.bom_len + raw_offset) as u32
+  }
+
+  /// Map the spans of the diagnostics to the raw input.
+  pub fn map_diagnostics(&self, diagnostics: &mut [DiagnosticMessage]) {
+    for span in diagnostics.iter_mut().filter_map(|diagnostic| diagnostic.span.as_mut()) {
+      span.start = self.raw_offset(span.start);
+      span.end = self.raw_offset(span.end);
+    }
+  }
+
+  /// Map the spans of the dependencies to the raw input.
+  pub fn map_deps(&self, deps: &mut [DependencyDescriptor]) {
+    for span in deps.iter_mut().filter_map(|dep| dep.span.as_mut()) {
+      span.start = self.raw_offset(span.start);
+      span.end = self.raw_offset(span.end);
+    }
+  }
+}
+
+/// Decode the source code as UTF-8, the UTF-8 BOM is stripped and the UTF-16 code with a BOM is transcoded.
+pub fn decode_source<'a>(filename: &str, bytes: &'a [u8]) -> Result<DecodedSource<'a>, DiagnosticBuffer> {
+  let (code, bom_len, utf16) = if let Some(bytes) = bytes.strip_prefix(&UTF8_BOM) {
+    (Cow::Borrowed(decode_utf8(filename, bytes, UTF8_BOM.len())?), UTF8_BOM.len(), false)
+  } else if let Some(bytes) = bytes.strip_prefix(&UTF16_LE_BOM) {
+    (
+      Cow::Owned(decode_utf16(filename, bytes, u16::from_le_bytes)?),
+      UTF16_LE_BOM.len(),
+      true,
+    )
+  } else if let Some(bytes) = bytes.strip_prefix(&UTF16_BE_BOM) {
+    (
+      Cow::Owned(decode_utf16(filename, bytes, u16::from_be_bytes)?),
+      UTF16_BE_BOM.len(),
+      true,
+    )
+  } else {
+    (Cow::Borrowed(decode_utf8(filename, bytes, 0)?), 0, false)
+  };
+  Ok(DecodedSource { code, bom_len, utf16 })
+}
+
+fn decode_utf8<'a>(filename: &str, bytes: &'a [u8], bom_len: usize) -> Result<&'a str, DiagnosticBuffer> {
+  std::str::from_utf8(bytes).map_err(|e| {
+    let valid = e.valid_up_to();
+    let prefix = std::str::from_utf8(&bytes[..valid]).unwrap();
+    let invalid_len = e.error_len().unwrap_or(bytes.len() - valid);
+    invalid_encoding_error(
+      filename,
+      "Invalid UTF-8 sequence",
+      prefix,
+      bom_len + valid,
+      bom_len + valid + invalid_len,
+    )
+  })
+}
+
+fn decode_utf16(filename: &str, bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, DiagnosticBuffer> {
+  let bom_len = UTF16_LE_BOM.len();
+  let units = bytes.chunks(2).map(|chunk| match chunk {
+    [a, b] => Ok(from_bytes([*a, *b])),
+    _ => Err(()),
+  });
+  let mut decoded = String::with_capacity(bytes.len() / 2);
+  let mut offset = bom_len;
+  for unit in char::decode_utf16(units.map_while(Result::ok)) {
+    match unit {
+      Ok(c) => {
+        decoded.push(c);
+        offset += c.len_utf16() * 2;
+      }
+      Err(_) => {
+        return Err(invalid_encoding_error(
+          filename,
+          "Invalid UTF-16 sequence, unpaired surrogate",
+          &decoded,
+          offset,
+          offset + 2,
+        ));
+      }
+    }
+  }
+  if !bytes.len().is_multiple_of(2) {
+    return Err(invalid_encoding_error(
+      filename,
+      "Invalid UTF-16 sequence, odd number of bytes",
+      &decoded,
+      offset,
+      offset + 1,
+    ));
+  }
+  Ok(decoded)
+}
+
+/// Create an error diagnostic at the given byte range of the raw input, the `prefix` is the decoded code before the range.
+fn invalid_encoding_error(filename: &str, message: &str, prefix: &str, start: usize, end: usize) -> DiagnosticBuffer {
+  let line = prefix.matches('\n').count() + 1;
+  let column = prefix.rsplit('\n').next().unwrap_or_default().chars().count();
+  DiagnosticBuffer(vec![DiagnosticMessage {
+    message: format!("{} (byte offset {})", message, start),
+    severity: Severity::Error,
+    filename: filename.into(),
+    start: Some(Position { line, column }),
+    end: Some(Position { line, column: column + 1 }),
+    span: Some(DiagnosticSpan {
+      start: start as u32,
+      end: end as u32,
+    }),
+    code_frame: None,
+  }])
+}
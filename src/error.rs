@@ -1,7 +1,8 @@
+use serde::Serialize;
 use std::fmt;
 use std::sync::{Arc, RwLock};
-use swc_common::errors::{Diagnostic, DiagnosticBuilder, Emitter};
-use swc_common::{Loc, Span};
+use swc_common::errors::{Diagnostic, DiagnosticBuilder, Emitter, Level};
+use swc_common::{Loc, SourceMap, Span};
 
 /// A buffer for collecting errors from the AST parser.
 #[derive(Debug, Clone)]
@@ -25,34 +26,162 @@ impl Emitter for ErrorBuffer {
   }
 }
 
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+  Error,
+  Warning,
+  Info,
+}
+
+impl From<Level> for Severity {
+  fn from(level: Level) -> Self {
+    match level {
+      Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => Severity::Error,
+      Level::Warning => Severity::Warning,
+      _ => Severity::Info,
+    }
+  }
+}
+
+/// A position in the source code, the line is 1-based and the column is 0-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize,
+}
+
+/// The byte range of a diagnostic in the raw input, e.g. the offsets of a UTF-16 input count the bytes of UTF-16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DiagnosticSpan {
+  pub start: u32,
+  pub end: u32,
+}
+
+/// A structured diagnostic message.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticMessage {
+  pub message: String,
+  pub severity: Severity,
+  pub filename: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub start: Option<Position>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub end: Option<Position>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub span: Option<DiagnosticSpan>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub code_frame: Option<String>,
+}
+
+impl DiagnosticMessage {
+  pub fn new(message: String, severity: Severity, filename: &str, span: Option<Span>, source_map: &SourceMap) -> Self {
+    let mut diagnostic = DiagnosticMessage {
+      message,
+      severity,
+      filename: filename.into(),
+      start: None,
+      end: None,
+      span: None,
+      code_frame: None,
+    };
+    if let Some(span) = span.filter(|span| !span.is_dummy()) {
+      let start = source_map.lookup_char_pos(span.lo);
+      let end = source_map.lookup_char_pos(span.hi);
+      diagnostic.start = Some(Position {
+        line: start.line,
+        column: start.col_display,
+      });
+      diagnostic.end = Some(Position {
+        line: end.line,
+        column: end.col_display,
+      });
+      diagnostic.span = Some(DiagnosticSpan {
+        start: source_map.lookup_byte_offset(span.lo).pos.0,
+        end: source_map.lookup_byte_offset(span.hi).pos.0,
+      });
+      diagnostic.code_frame = render_code_frame(&start, &end);
+    }
+    diagnostic
+  }
+
+  /// Create a warning which is not associated with any location of the source code.
+  pub fn warning(message: String, filename: &str) -> Self {
+    DiagnosticMessage {
+      message,
+      severity: Severity::Warning,
+      filename: filename.into(),
+      start: None,
+      end: None,
+      span: None,
+      code_frame: None,
+    }
+  }
+}
+
+impl fmt::Display for DiagnosticMessage {
+  fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(start) = &self.start {
+      write!(fmt, "{} at {}:{}:{}", self.message, self.filename, start.line, start.column)
+    } else {
+      fmt.write_str(&self.message)
+    }
+  }
+}
+
 /// A buffer for collecting diagnostic messages from the AST parser.
 #[derive(Debug)]
-pub struct DiagnosticBuffer(Vec<String>);
+pub struct DiagnosticBuffer(pub Vec<DiagnosticMessage>);
 
 impl fmt::Display for DiagnosticBuffer {
   fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-    fmt.pad(&self.0.join(","))
+    let messages: Vec<String> = self.0.iter().map(|d| d.to_string()).collect();
+    fmt.pad(&messages.join(","))
   }
 }
 
 impl DiagnosticBuffer {
-  pub fn from_error_buffer<F>(error_buffer: ErrorBuffer, get_loc: F) -> Self
-  where
-    F: Fn(Span) -> Loc,
-  {
+  pub fn from_error_buffer(error_buffer: ErrorBuffer, source_map: &SourceMap) -> Self {
     let diagnostics = error_buffer.diagnostics.read().unwrap().clone();
     let diagnostics = diagnostics
       .iter()
       .map(|d| {
-        let mut message = d.message();
-        if let Some(span) = d.span.primary_span() {
-          let loc = get_loc(span);
-          message = format!("{} at {}:{}:{}", message, error_buffer.specifier, loc.line, loc.col_display);
-        }
-        message
+        DiagnosticMessage::new(
+          d.message(),
+          d.level.into(),
+          &error_buffer.specifier,
+          d.span.primary_span(),
+          source_map,
+        )
       })
       .collect();
 
     Self(diagnostics)
   }
 }
+
+/// Render the source line of the diagnostic with a `^^^` marker under the span, e.g.
+/// ```text
+/// 1 | export App() {}
+///   |        ^^^
+/// ```
+fn render_code_frame(start: &Loc, end: &Loc) -> Option<String> {
+  let line = start.file.get_line(start.line - 1)?;
+  let line = line.trim_end_matches('\r');
+  let gutter = start.line.to_string();
+  let width = if end.line == start.line {
+    end.col_display.saturating_sub(start.col_display)
+  } else {
+    line.chars().count().saturating_sub(start.col_display)
+  };
+  Some(format!(
+    "{} | {}\n{} | {}{}",
+    gutter,
+    line,
+    " ".repeat(gutter.len()),
+    " ".repeat(start.col_display),
+    "^".repeat(width.max(1))
+  ))
+}
@@ -1,12 +1,43 @@
-use crate::resolver::Resolver;
+use crate::error::{DiagnosticMessage, Severity};
+use crate::resolver::{DependencyKind, DependencySpan, Resolver};
 use crate::swc_helpers::*;
+use indexmap::IndexMap;
 use std::cell::RefCell;
 use std::rc::Rc;
+use swc_common::{SourceMap, Span};
 use swc_ecmascript::ast::*;
-use swc_ecmascript::visit::{Fold, FoldWith, noop_fold_type};
+use swc_ecmascript::visit::{Fold, FoldWith, VisitMut, noop_fold_type, noop_visit_mut_type};
 
 pub struct ImportAnalyzer {
   pub resolver: Rc<RefCell<Resolver>>,
+  pub source_map: Rc<SourceMap>,
+}
+
+impl ImportAnalyzer {
+  /// Resolve the specifier and record the dependency of the import/export statement at the given span.
+  fn resolve(&self, specifier: &str, kind: DependencyKind, attributes: Option<IndexMap<String, String>>, span: Span) -> String {
+    let mut resolver = self.resolver.borrow_mut();
+    if resolver.is_unresolved_bare_specifier(specifier) {
+      let warning = DiagnosticMessage::new(
+        format!("Unresolved bare specifier \"{}\", it's not found in the import map", specifier),
+        Severity::Warning,
+        &resolver.filename,
+        Some(span),
+        &self.source_map,
+      );
+      resolver.warnings.push(warning);
+    }
+    // the span is dummy for generated imports, e.g. the jsx runtime import
+    let dep_span = if span.is_dummy() {
+      None
+    } else {
+      Some(DependencySpan {
+        start: self.source_map.lookup_byte_offset(span.lo).pos.0,
+        end: self.source_map.lookup_byte_offset(span.hi).pos.0,
+      })
+    };
+    resolver.resolve(specifier, kind, attributes, dep_span)
+  }
 }
 
 impl Fold for ImportAnalyzer {
@@ -26,23 +57,20 @@ impl Fold for ImportAnalyzer {
                 // ingore type import
                 ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))
               } else {
-                let mut resolver = self.resolver.borrow_mut();
-                let with_type = if let Some(with) = import_decl.with.as_ref() {
-                  if let Some(Expr::Lit(Lit::Str(s))) = get_object_value(with, "type") {
-                    Some(s.value.to_string_lossy().into_owned())
-                  } else {
-                    None
-                  }
-                } else {
-                  None
-                };
+                let attributes = import_decl.with.as_deref().map(get_object_str_map);
+                let with_type = attributes.as_ref().and_then(|attrs| attrs.get("type"));
                 // remove `with { type: "rpc" }` from import declaration
-                let with = if with_type.as_ref().is_none_or(|e| e != "rpc") {
+                let with = if with_type.is_none_or(|t| t != "rpc") {
                   import_decl.with
                 } else {
                   None
                 };
-                let resolved_url = resolver.resolve(import_decl.src.value.to_string_lossy().as_ref(), with_type);
+                let resolved_url = self.resolve(
+                  import_decl.src.value.to_string_lossy().as_ref(),
+                  DependencyKind::Static,
+                  attributes,
+                  import_decl.span,
+                );
                 ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
                   src: Box::new(new_str(&resolved_url)),
                   with,
@@ -69,8 +97,12 @@ impl Fold for ImportAnalyzer {
                   with,
                 }))
               } else {
-                let mut resolver = self.resolver.borrow_mut();
-                let resolved_url = resolver.resolve(src.value.to_string_lossy().as_ref(), None);
+                let resolved_url = self.resolve(
+                  src.value.to_string_lossy().as_ref(),
+                  DependencyKind::ReExport,
+                  with.as_deref().map(get_object_str_map),
+                  span,
+                );
                 ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
                   span,
                   specifiers,
@@ -82,8 +114,12 @@ impl Fold for ImportAnalyzer {
             }
             // match: export * from "https://esm.sh/react"
             ModuleDecl::ExportAll(export_all) => {
-              let mut resolver = self.resolver.borrow_mut();
-              let resolved_url = resolver.resolve(export_all.src.value.to_string_lossy().as_ref(), None);
+              let resolved_url = self.resolve(
+                export_all.src.value.to_string_lossy().as_ref(),
+                DependencyKind::ExportAll,
+                export_all.with.as_deref().map(get_object_str_map),
+                export_all.span,
+              );
               ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll {
                 src: Box::new(new_str(&resolved_url)),
                 ..export_all
@@ -113,21 +149,17 @@ impl Fold for ImportAnalyzer {
         _ => None,
       };
       if let Some(src) = src {
-        let mut resolver = self.resolver.borrow_mut();
-        let with_type = match call.args.get(1) {
+        let attributes = match call.args.get(1) {
           Some(ExprOrSpread { expr, .. }) => match expr.as_ref() {
             Expr::Object(obj) => match get_object_value(obj, "with") {
-              Some(Expr::Object(obj)) => match get_object_value(obj, "type") {
-                Some(Expr::Lit(Lit::Str(s))) => Some(s.value.to_string_lossy().into_owned()),
-                _ => None,
-              },
+              Some(Expr::Object(obj)) => Some(get_object_str_map(obj)),
               _ => None,
             },
             _ => None,
           },
           _ => None,
         };
-        let new_src = resolver.resolve(src.value.to_string_lossy().as_ref(), with_type);
+        let new_src = self.resolve(src.value.to_string_lossy().as_ref(), DependencyKind::Dynamic, attributes, call.span);
         call.args[0] = ExprOrSpread {
           spread: None,
           expr: Box::new(Expr::Lit(Lit::Str(new_str(&new_src)))),
@@ -137,3 +169,24 @@ impl Fold for ImportAnalyzer {
     call.fold_children_with(self)
   }
 }
+
+/// Resolve the `@swc/helpers` imports, they are injected by the `inject_helpers` pass after the imports are analyzed.
+pub struct HelperImportResolver {
+  pub analyzer: ImportAnalyzer,
+}
+
+impl VisitMut for HelperImportResolver {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+    for item in items.iter_mut() {
+      if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+        let specifier = import_decl.src.value.to_string_lossy();
+        if specifier.starts_with("@swc/helpers/") {
+          let resolved_url = self.analyzer.resolve(&specifier, DependencyKind::Static, None, import_decl.span);
+          *import_decl.src = new_str(&resolved_url);
+        }
+      }
+    }
+  }
+}
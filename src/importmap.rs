@@ -0,0 +1,122 @@
+use crate::error::DiagnosticMessage;
+use crate::specifier::is_http_specifier;
+use import_map::{ImportMap, ImportMapDiagnostic};
+#[cfg(feature = "wasm")]
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+use url::Url;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/// A parsed import map that can be shared by many `transform` calls.
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "ImportMap"))]
+pub struct CompiledImportMap {
+  import_map: Rc<ImportMap>,
+  diagnostics: Vec<DiagnosticMessage>,
+  /// the detected jsx import source, keyed by the dev mode and the scopes that match the referrer.
+  jsx_import_source_cache: RefCell<HashMap<(bool, String), String>>,
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_class = "ImportMap")]
+impl CompiledImportMap {
+  #[wasm_bindgen(constructor)]
+  pub fn new(import_map: JsValue) -> Result<CompiledImportMap, JsError> {
+    let import_map_raw: serde_json::Value = serde_wasm_bindgen::from_value(import_map).map_err(|e| JsError::new(&e.to_string()))?;
+    Self::parse(import_map_raw).map_err(|e| JsError::new(&e))
+  }
+
+  /// The non-fatal issues found while parsing the import map.
+  #[wasm_bindgen(getter, js_name = "diagnostics")]
+  pub fn diagnostics_js(&self) -> JsValue {
+    self.diagnostics.serialize(&serde_wasm_bindgen::Serializer::new()).unwrap()
+  }
+}
+
+impl CompiledImportMap {
+  /// Parse the import map, the non-standard `$src` field specifies the URL of the import map.
+  pub fn parse(import_map_raw: serde_json::Value) -> Result<Self, String> {
+    let Some(import_map_obj) = import_map_raw.as_object() else {
+      return Err("Invalid import map, must be an object".to_owned());
+    };
+    let im_src = import_map_obj.get("$src").and_then(|src| src.as_str()).map(|s| {
+      if s.starts_with('/') {
+        "file://".to_owned() + s
+      } else {
+        s.to_owned()
+      }
+    });
+    let src = match Url::from_str(im_src.clone().unwrap_or("file:///anonymous_import_map.json".to_owned()).as_str()) {
+      Ok(url) => url,
+      Err(_) => {
+        return Err("Invalid \"$src\" in import map, must be a valid URL but got ".to_owned() + im_src.unwrap().as_str());
+      }
+    };
+    let ret = import_map::parse_from_value(src.clone(), import_map_raw).map_err(|e| e.to_string())?;
+    let diagnostics = ret
+      .diagnostics
+      .iter()
+      // `$src` is a non-standard key used to specify the import map URL
+      .filter(|diagnostic| !matches!(diagnostic, ImportMapDiagnostic::InvalidTopLevelKey(key) if key == "$src"))
+      .map(|diagnostic| DiagnosticMessage::warning(format!("Import map: {}", diagnostic), src.as_str()))
+      .collect();
+    Ok(CompiledImportMap {
+      import_map: Rc::new(ret.import_map),
+      diagnostics,
+      jsx_import_source_cache: RefCell::new(HashMap::new()),
+    })
+  }
+
+  pub fn import_map(&self) -> Rc<ImportMap> {
+    self.import_map.clone()
+  }
+
+  pub fn diagnostics(&self) -> &[DiagnosticMessage] {
+    &self.diagnostics
+  }
+
+  /// Detect the jsx import source by checking the `*/jsx-runtime` specifiers in the import map.
+  pub fn jsx_import_source(&self, filename: &str, is_dev: bool) -> String {
+    let referrer = if is_http_specifier(filename) {
+      Url::from_str(filename).unwrap()
+    } else {
+      Url::from_str(&("file://".to_owned() + filename.trim_start_matches('.'))).unwrap()
+    };
+    let scopes = self
+      .import_map
+      .scopes()
+      .filter(|scope| referrer.as_str() == scope.key || (scope.key.ends_with('/') && referrer.as_str().starts_with(scope.key)))
+      .map(|scope| scope.key)
+      .collect::<Vec<_>>()
+      .join("\n");
+    let mut cache = self.jsx_import_source_cache.borrow_mut();
+    cache
+      .entry((is_dev, scopes))
+      .or_insert_with(|| self.detect_jsx_import_source(&referrer, is_dev))
+      .clone()
+  }
+
+  fn detect_jsx_import_source(&self, referrer: &Url, is_dev: bool) -> String {
+    let jsx_runtime_path = if is_dev { "/jsx-dev-runtime" } else { "/jsx-runtime" };
+    let specifier = self
+      .import_map
+      .imports()
+      .keys()
+      .find(|k| k.ends_with(jsx_runtime_path))
+      .map(|k| k.trim_end_matches(jsx_runtime_path).to_owned());
+    if let Some(specifier) = specifier {
+      return specifier;
+    }
+    let possible_jsx_libs = vec!["react", "preact", "solid-js", "mono-jsx/dom", "mono-jsx", "vue"];
+    for possible_jsx_lib in possible_jsx_libs {
+      let possible_jsx_import_source_path = possible_jsx_lib.to_owned() + jsx_runtime_path;
+      if self.import_map.resolve(possible_jsx_import_source_path.as_str(), referrer).is_ok() {
+        return possible_jsx_lib.to_owned();
+      }
+    }
+    "react".to_owned()
+  }
+}
@@ -1,29 +1,52 @@
 mod dev;
+mod encoding;
 mod error;
 mod import_analyzer;
+mod importmap;
 mod resolver;
 mod specifier;
 mod swc;
+mod swc_compat;
+mod swc_define;
 mod swc_helpers;
+mod swc_jsx_precompile;
 mod swc_jsx_src;
+mod swc_minify;
+mod swc_polyfill;
 mod swc_prefresh;
+mod swc_solid_jsx;
+mod swc_tree_shaking;
+mod swc_vue_jsx;
+mod targets;
+mod tsconfig;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 #[cfg(test)]
 mod test;
 
-use dev::DevOptions;
+pub use dev::{DevOptions, HmrOptions, JsxSourceOptions, RefreshOptions};
+pub use error::{DiagnosticMessage, DiagnosticSpan, Position, Severity};
+pub use importmap::CompiledImportMap;
+pub use resolver::{DependencyDescriptor, DependencyKind, DependencySpan};
+pub use swc_minify::{Minify, MinifyOptions};
+pub use swc_solid_jsx::{SolidGenerate, SolidOptions};
+pub use swc_tree_shaking::UsedExports;
+
+use error::DiagnosticBuffer;
+use indexmap::IndexMap;
 use resolver::Resolver;
 use serde::{Deserialize, Serialize};
-use specifier::is_http_specifier;
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::str::{FromStr, from_utf8_unchecked};
-use swc::{EmitOptions, SWC};
+use swc::{DecoratorsVersion, EmitOptions, EmitOutput, JsxRuntime, SWC};
+use swc_compat::Feature;
+use swc_define::Define;
 use swc_ecmascript::ast::EsVersion;
-use url::Url;
-use wasm_bindgen::prelude::*;
+use targets::Browsers;
+use tsconfig::TsConfig;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone, Default)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct SWCTransformOptions {
   pub filename: String,
@@ -32,117 +55,238 @@ pub struct SWCTransformOptions {
   pub lang: Option<String>,
   pub source_map: Option<String>,
   pub import_map: Option<serde_json::Value>,
+  pub tsconfig: Option<serde_json::Value>,
   pub dev: Option<DevOptions>,
   pub target: Option<String>,
+  pub define: Option<IndexMap<String, String>>,
+  pub env: Option<IndexMap<String, serde_json::Value>>,
+  pub polyfills: Option<bool>,
+  pub external_helpers: Option<bool>,
+  pub jsx_runtime: Option<String>,
   pub jsx_import_source: Option<String>,
-  pub minify: Option<bool>,
+  pub jsx_factory: Option<String>,
+  pub jsx_fragment_factory: Option<String>,
+  pub jsx_precompile_skip_elements: Option<Vec<String>>,
+  pub solid: Option<SolidOptions>,
+  pub decorators: Option<String>,
+  pub emit_decorator_metadata: Option<bool>,
+  pub minify: Option<Minify>,
   pub tree_shaking: Option<bool>,
+  pub used_exports: Option<UsedExports>,
+  pub fail_on_recovered_errors: Option<bool>,
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SWCTransformOutput {
   #[serde(with = "serde_bytes")]
-  code: Vec<u8>,
+  pub code: Vec<u8>,
   #[serde(with = "serde_bytes")]
-  map: Option<Vec<u8>>,
+  pub map: Option<Vec<u8>>,
+  pub deps: Vec<DependencyDescriptor>,
+  pub warnings: Vec<DiagnosticMessage>,
+  /// the syntax features of the module that are lowered for the target, e.g. `"optional-chaining"`.
+  pub lowered_features: Vec<String>,
+  /// the resolved JSX runtime of the module, the JSX is kept in the code if it's `"preserve"`.
+  pub jsx_runtime: String,
+}
+
+/// The error of transforming a module.
+#[derive(Debug, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct SWCTransformError {
+  pub message: String,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub diagnostics: Vec<DiagnosticMessage>,
+}
+
+impl SWCTransformError {
+  fn new(message: String) -> Self {
+    SWCTransformError {
+      message,
+      diagnostics: vec![],
+    }
+  }
+}
+
+impl From<DiagnosticBuffer> for SWCTransformError {
+  fn from(diagnostics: DiagnosticBuffer) -> Self {
+    SWCTransformError {
+      message: diagnostics.to_string(),
+      diagnostics: diagnostics.0,
+    }
+  }
 }
 
-#[wasm_bindgen(js_name = "transform")]
-pub fn transform(swc_transform_options: JsValue) -> Result<JsValue, JsError> {
-  let options: SWCTransformOptions = serde_wasm_bindgen::from_value(swc_transform_options).expect("could not parse options");
+/// Transforms the module, the `import_map` option is parsed for every call,
+/// use `transform_with_import_map` to share a parsed import map between modules.
+pub fn transform(options: &SWCTransformOptions) -> Result<SWCTransformOutput, SWCTransformError> {
+  let import_map = match &options.import_map {
+    Some(import_map_raw) => Some(CompiledImportMap::parse(import_map_raw.clone()).map_err(SWCTransformError::new)?),
+    None => None,
+  };
+  let mut output = transform_module(options, import_map.as_ref())?;
+  if let Some(import_map) = &import_map {
+    output.warnings.splice(0..0, import_map.diagnostics().iter().cloned());
+  }
+  Ok(output)
+}
+
+/// Transforms the module with a parsed import map.
+pub fn transform_with_import_map(
+  options: &SWCTransformOptions,
+  import_map: &CompiledImportMap,
+) -> Result<SWCTransformOutput, SWCTransformError> {
+  if options.import_map.is_some() {
+    return Err(SWCTransformError::new(
+      "The \"importMap\" option can not be used with a parsed import map".to_owned(),
+    ));
+  }
+  transform_module(options, Some(import_map))
+}
+
+fn transform_module(
+  options: &SWCTransformOptions,
+  import_map: Option<&CompiledImportMap>,
+) -> Result<SWCTransformOutput, SWCTransformError> {
   let filename = options.filename.as_str();
-  let im = if let Some(import_map_raw) = options.import_map {
-    let im_src = if let Some(src) = import_map_raw.as_object().unwrap().get("$src") {
-      src.as_str().map(|s| {
-        if s.starts_with('/') {
-          "file://".to_owned() + s
-        } else {
-          s.to_owned()
-        }
-      })
-    } else {
-      None
-    };
-    let src = match Url::from_str(im_src.clone().unwrap_or("file:///anonymous_import_map.json".to_owned()).as_str()) {
-      Ok(url) => url,
-      Err(_) => {
-        return Err(
-          JsError::new(("Invalid \"$src\" in import map, must be a valid URL but got ".to_owned() + im_src.unwrap().as_str()).as_str())
-            .into(),
-        );
-      }
-    };
-    match import_map::parse_from_value(src, import_map_raw) {
-      Ok(import_map) => Some(import_map.import_map),
-      Err(e) => {
-        return Err(JsError::new(&e.to_string()).into());
-      }
+  let mut warnings = Vec::new();
+  let resolver = Rc::new(RefCell::new(Resolver::new(filename, import_map.map(|im| im.import_map()))));
+  let compiler_options = match &options.tsconfig {
+    Some(tsconfig_raw) => {
+      let tsconfig = TsConfig::parse(tsconfig_raw.clone()).map_err(SWCTransformError::new)?;
+      resolver.borrow_mut().path_aliases = tsconfig.path_aliases();
+      tsconfig.compiler_options
     }
-  } else {
-    None
+    None => Default::default(),
+  };
+  if let Some(jsx) = compiler_options.jsx.as_deref()
+    && !matches!(
+      jsx,
+      "react" | "react-jsx" | "react-jsxdev" | "preserve" | "react-native" | "precompile"
+    )
+  {
+    warnings.push(DiagnosticMessage::warning(
+      format!("Unsupported jsx \"{}\" in tsconfig, using \"react-jsx\"", jsx),
+      filename,
+    ));
+  }
+  let (target, browsers) = match options.target.as_deref() {
+    Some(target) => match parse_target(target) {
+      Some(target) => (target, None),
+      // a browserslist query or a User-Agent
+      None => match Browsers::parse(target) {
+        Ok(browsers) => (browsers.features().es_version(), Some(browsers)),
+        Err(e) => (unsupported_target(target, &format!(": {}", e), filename, &mut warnings), None),
+      },
+    },
+    None => (
+      match compiler_options.target.as_deref() {
+        Some(target) => parse_target(target).unwrap_or_else(|| unsupported_target(target, " in tsconfig", filename, &mut warnings)),
+        None => EsVersion::EsNext,
+      },
+      None,
+    ),
   };
-  let resolver = Rc::new(RefCell::new(Resolver::new(filename, im.to_owned())));
-  let target = match options.target.unwrap_or("esnext".into()).to_lowercase().as_str() {
-    "es2015" => EsVersion::Es2015,
-    "es2016" => EsVersion::Es2016,
-    "es2017" => EsVersion::Es2017,
-    "es2018" => EsVersion::Es2018,
-    "es2019" => EsVersion::Es2019,
-    "es2020" => EsVersion::Es2020,
-    "es2021" => EsVersion::Es2021,
-    "es2022" => EsVersion::Es2022,
-    "es2023" => EsVersion::Es2023,
-    "es2024" => EsVersion::Es2024,
-    "esnext" => EsVersion::EsNext,
-    t => {
-      return Err(JsError::new(("Invalid target: ".to_owned() + t).as_str()).into());
+  let define = match &options.define {
+    Some(define) => define
+      .iter()
+      .map(|(key, value)| Define::parse(key, value))
+      .collect::<Result<Vec<_>, _>>()
+      .map_err(SWCTransformError::new)?,
+    None => vec![],
+  };
+  let used_exports = match &options.used_exports {
+    Some(UsedExports::Names(names)) => Some(names.clone()),
+    Some(UsedExports::Keyword(keyword)) if keyword == "none" => Some(vec![]),
+    Some(UsedExports::Keyword(keyword)) => {
+      return Err(SWCTransformError::new(format!("Invalid usedExports: {}", keyword)));
     }
+    None => None,
   };
-  let code = unsafe { from_utf8_unchecked(&options.code) };
-  let module = match SWC::parse(filename, code, options.lang) {
-    Ok(ret) => ret,
+  let decorators = match options.decorators.as_deref() {
+    Some("legacy") => Some(DecoratorsVersion::Legacy),
+    Some("2023-11") => Some(DecoratorsVersion::V202311),
+    Some("none") => Some(DecoratorsVersion::None),
+    Some(v) => {
+      return Err(SWCTransformError::new("Invalid decorators: ".to_owned() + v));
+    }
+    None => compiler_options.experimental_decorators.map(|experimental| {
+      if experimental {
+        DecoratorsVersion::Legacy
+      } else {
+        DecoratorsVersion::V202311
+      }
+    }),
+  };
+  let source = match encoding::decode_source(filename, &options.code) {
+    Ok(source) => source,
     Err(err) => {
-      return Err(JsError::new(&err.to_string()).into());
+      return Err(err.into());
     }
   };
-  let jsx_import_source = if let Some(jsx_import_source) = options.jsx_import_source {
+  let module = match SWC::parse(filename, &source.code, options.lang.clone()) {
+    Ok(ret) => ret,
+    Err(mut err) => {
+      source.map_diagnostics(&mut err.0);
+      return Err(err.into());
+    }
+  };
+  if !module.recovered_errors.is_empty() {
+    if options.fail_on_recovered_errors.unwrap_or_default() {
+      let mut err = DiagnosticBuffer(module.recovered_errors);
+      source.map_diagnostics(&mut err.0);
+      return Err(err.into());
+    }
+    for diagnostic in &module.recovered_errors {
+      warnings.push(DiagnosticMessage {
+        severity: Severity::Warning,
+        ..diagnostic.clone()
+      });
+    }
+  }
+  // the pragmas in the file take precedence over the options
+  let jsx_pragmas = module.jsx_pragmas();
+  let jsx_runtime = match jsx_pragmas.runtime.as_deref().or(options.jsx_runtime.as_deref()) {
+    Some("automatic") => JsxRuntime::Automatic,
+    Some("classic") => JsxRuntime::Classic,
+    Some("preserve") => JsxRuntime::Preserve,
+    Some("precompile") => JsxRuntime::Precompile,
+    Some(v) => {
+      return Err(SWCTransformError::new("Invalid jsxRuntime: ".to_owned() + v));
+    }
+    None if jsx_pragmas.import_source.is_some() => JsxRuntime::Automatic,
+    None if jsx_pragmas.factory.is_some() || jsx_pragmas.fragment_factory.is_some() => JsxRuntime::Classic,
+    None if options.jsx_import_source.is_some() => JsxRuntime::Automatic,
+    None if options.jsx_factory.is_some() || options.jsx_fragment_factory.is_some() => JsxRuntime::Classic,
+    None => match compiler_options.jsx.as_deref() {
+      Some("react") => JsxRuntime::Classic,
+      Some("preserve") | Some("react-native") => JsxRuntime::Preserve,
+      // the `jsx` option of deno.json
+      Some("precompile") => JsxRuntime::Precompile,
+      _ => JsxRuntime::Automatic,
+    },
+  };
+  let jsx_import_source = if !matches!(jsx_runtime, JsxRuntime::Automatic | JsxRuntime::Precompile) {
+    None
+  } else if let Some(jsx_import_source) = jsx_pragmas
+    .import_source
+    .or(options.jsx_import_source.clone())
+    .or(compiler_options.jsx_import_source)
+  {
     Some(jsx_import_source)
-  } else if let Some(importmap) = im {
+  } else if jsx_runtime == JsxRuntime::Precompile {
+    // the runtime must export the `jsxTemplate`, `jsxAttr` and `jsxEscape` functions, e.g. preact, react does not
+    return Err(SWCTransformError::new(
+      "The precompile jsxRuntime requires the jsxImportSource, e.g. \"preact\"".to_owned(),
+    ));
+  } else if let Some(import_map) = import_map {
     // check `jsxImportSource` from import map
-    let referrer = if is_http_specifier(filename) {
-      Url::from_str(filename).unwrap()
-    } else {
-      Url::from_str(&("file://".to_owned() + filename.trim_start_matches('.'))).unwrap()
-    };
-    let jsx_runtime_path = if options.dev.is_some() {
-      "/jsx-dev-runtime".to_owned()
-    } else {
-      "/jsx-runtime".to_owned()
-    };
-    let specifier = importmap
-      .imports()
-      .keys()
-      .find(|k| k.ends_with(&jsx_runtime_path))
-      .map(|k| k.trim_end_matches(&jsx_runtime_path).to_owned());
-    if specifier.is_some() {
-      Some(specifier.unwrap())
-    } else {
-      let possible_jsx_libs = vec!["react", "preact", "solid-js", "mono-jsx/dom", "mono-jsx", "vue"];
-      let mut jsx_import_source = Some("react".to_owned());
-      for possible_jsx_lib in possible_jsx_libs {
-        let possible_jsx_import_source_path = possible_jsx_lib.to_owned();
-        let possible_jsx_import_source_path = possible_jsx_import_source_path + jsx_runtime_path.as_str();
-        if importmap.resolve(possible_jsx_import_source_path.as_str(), &referrer).is_ok() {
-          jsx_import_source = Some(possible_jsx_lib.to_owned());
-          break;
-        }
-      }
-      jsx_import_source
-    }
+    Some(import_map.jsx_import_source(filename, options.dev.is_some()))
   } else {
     Some("react".to_owned())
   };
-  let source_map = if let Some(source_map) = options.source_map {
+  let source_map = if let Some(source_map) = &options.source_map {
     match source_map.as_str() {
       "inline" => Some("inline".to_owned()),
       "external" => Some("external".to_owned()),
@@ -151,20 +295,94 @@ pub fn transform(swc_transform_options: JsValue) -> Result<JsValue, JsError> {
   } else {
     None
   };
+  // TypeScript uses the `[[Define]]` semantics by default for the targets with the native class fields, e.g. ES2022
+  let use_define_for_class_fields = compiler_options.use_define_for_class_fields.unwrap_or_else(|| match &browsers {
+    Some(browsers) => !browsers.features().contains(Feature::ClassProperties),
+    None => target >= EsVersion::Es2022,
+  });
   let emit_options = EmitOptions {
     target,
+    browsers,
+    define,
+    env: options.env.clone(),
+    external_helpers: options.external_helpers.unwrap_or_default(),
+    polyfills: options.polyfills.unwrap_or_default(),
+    jsx_runtime: Some(jsx_runtime),
     jsx_import_source,
+    jsx_factory: jsx_pragmas.factory.or(options.jsx_factory.clone()).or(compiler_options.jsx_factory),
+    jsx_fragment_factory: jsx_pragmas
+      .fragment_factory
+      .or(options.jsx_fragment_factory.clone())
+      .or(compiler_options.jsx_fragment_factory),
+    jsx_precompile_skip_elements: options
+      .jsx_precompile_skip_elements
+      .clone()
+      .or(compiler_options.jsx_precompile_skip_elements)
+      .unwrap_or_default(),
+    solid: options.solid.clone().unwrap_or_default(),
+    decorators,
+    emit_decorator_metadata: options
+      .emit_decorator_metadata
+      .or(compiler_options.emit_decorator_metadata)
+      .unwrap_or_default(),
+    use_define_for_class_fields,
+    verbatim_module_syntax: compiler_options.verbatim_module_syntax.unwrap_or_default(),
     source_map,
-    dev: options.dev,
-    minify: options.minify.unwrap_or_default(),
+    dev: options.dev.clone(),
+    minify: options.minify.as_ref().and_then(Minify::options),
     tree_shaking: options.tree_shaking.unwrap_or_default(),
+    used_exports,
   };
-  let (code, map) = match module.transform(resolver.clone(), &emit_options) {
+  let EmitOutput {
+    code,
+    map,
+    lowered_features,
+  } = match module.transform(resolver.clone(), &emit_options) {
     Ok(ret) => ret,
     Err(e) => {
-      return Err(JsError::new(&e.to_string()).into());
+      return Err(SWCTransformError::new(e.to_string()));
     }
   };
 
-  Ok(serde_wasm_bindgen::to_value(&SWCTransformOutput { code, map }).unwrap())
+  let mut deps = resolver.borrow().deps.clone();
+  warnings.append(&mut resolver.borrow_mut().warnings);
+  // the spans point to the raw input, e.g. the UTF-16 bytes
+  source.map_deps(&mut deps);
+  source.map_diagnostics(&mut warnings);
+  Ok(SWCTransformOutput {
+    code,
+    map,
+    deps,
+    warnings,
+    lowered_features: lowered_features.iter().map(|f| f.name().to_owned()).collect(),
+    jsx_runtime: jsx_runtime.name().to_owned(),
+  })
+}
+
+/// Warn about an unsupported target and fall back to a conservative target, e.g. `"ES5"` or `"last 2 versions"`,
+/// the `reason` is appended to the message.
+fn unsupported_target(target: &str, reason: &str, filename: &str, warnings: &mut Vec<DiagnosticMessage>) -> EsVersion {
+  warnings.push(DiagnosticMessage::warning(
+    format!("Unsupported target \"{}\"{}, using \"es2015\"", target, reason),
+    filename,
+  ));
+  EsVersion::Es2015
+}
+
+/// Parse the ECMAScript target, it's case-insensitive to accept the `target` of tsconfig, e.g. `"ES2020"`.
+fn parse_target(target: &str) -> Option<EsVersion> {
+  match target.to_lowercase().as_str() {
+    "es2015" | "es6" => Some(EsVersion::Es2015),
+    "es2016" => Some(EsVersion::Es2016),
+    "es2017" => Some(EsVersion::Es2017),
+    "es2018" => Some(EsVersion::Es2018),
+    "es2019" => Some(EsVersion::Es2019),
+    "es2020" => Some(EsVersion::Es2020),
+    "es2021" => Some(EsVersion::Es2021),
+    "es2022" => Some(EsVersion::Es2022),
+    "es2023" => Some(EsVersion::Es2023),
+    "es2024" => Some(EsVersion::Es2024),
+    "esnext" => Some(EsVersion::EsNext),
+    _ => None,
+  }
 }
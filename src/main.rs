@@ -0,0 +1,530 @@
+use indexmap::IndexMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tsx::{
+  CompiledImportMap, DevOptions, HmrOptions, JsxSourceOptions, Minify, RefreshOptions, SWCTransformOptions, SolidGenerate, SolidOptions,
+  UsedExports,
+};
+
+const USAGE: &str = r#"Usage: tsx [options] <file|dir>
+
+Transpiles a TypeScript/JSX file, or all the modules in a directory, to JavaScript.
+
+Options:
+  -o, --out-dir <dir>            Write the output files to the directory, required for a directory input.
+                                 Prints the output code to stdout if not set.
+      --root <dir>               The root directory that the module filenames are relative to, default is the current directory.
+      --import-map <file>        The import map file.
+      --tsconfig <file>          The tsconfig.json or deno.json file, its "paths" are relative to the root directory.
+      --target <target>          The transform target, e.g. "es2020" or "chrome >= 87, safari >= 14", default is "esnext".
+                                 Only the version queries of browserslist are supported, the others fall back to "es2015".
+      --define <key=value>       Replace the global expression with the value, e.g. process.env.NODE_ENV='"production"'.
+                                 It can be specified multiple times.
+      --env <key=value>          Expose the string variable as import.meta.env.<key>, it can be specified multiple times.
+      --polyfills                Import the core-js polyfills of the APIs that the target does not support.
+      --external-helpers         Import the helpers from "@swc/helpers" instead of inlining them.
+      --lang <lang>              The code language, default is using the file extension.
+      --jsx-import-source <src>  The JSX import source, default is detected from the import map.
+      --jsx-runtime <runtime>    The JSX runtime, "automatic", "classic", "preserve" or "precompile", default is "automatic".
+                                 The output files of "preserve" have the ".jsx" extension,
+                                 "precompile" requires the --jsx-import-source, e.g. "preact".
+      --jsx-precompile-skip <elements>
+                                 The comma-separated elements that are not serialized by the "precompile" runtime.
+      --jsx-factory <name>       The function of the classic JSX runtime, default is "React.createElement".
+      --jsx-fragment <name>      The fragment of the classic JSX runtime, default is "React.Fragment".
+      --solid-ssr                Generate the server rendering code for the Solid JSX, used when the JSX import source is "solid-js".
+      --decorators <version>     The decorators proposal, "legacy", "2023-11" or "none",
+                                 default is "legacy" for TypeScript and "2023-11" for JavaScript.
+      --emit-decorator-metadata  Emit the design-time type metadata for the legacy decorators.
+      --minify                   Minify the output code, the names are mangled and the dead code is dropped.
+      --tree-shaking             Strip unused code.
+      --used-exports <names>     The comma-separated exports that are used by the other modules, or "none",
+                                 the other exports are dropped and the code they leave is stripped.
+      --source-map <type>        Create source map, "inline" or "external", "external" requires the --out-dir.
+      --dev                      Development mode.
+      --hmr <url>                Enable hot module replacement with the HMR runtime URL, implies --dev.
+      --refresh <url>            Enable react refresh with the refresh runtime URL, implies --dev.
+      --prefresh <url>           Enable preact refresh with the refresh runtime URL, implies --dev.
+      --jsx-source               Add `__source` props to JSX elements, implies --dev.
+      --deps                     Print the dependency graph of the modules as JSON instead of the code.
+  -h, --help                     Print this help message.
+"#;
+
+const EXTENSIONS: [&str; 6] = ["js", "mjs", "jsx", "ts", "mts", "tsx"];
+
+#[derive(Default)]
+struct Args {
+  input: Option<PathBuf>,
+  out_dir: Option<PathBuf>,
+  root: Option<PathBuf>,
+  import_map: Option<PathBuf>,
+  tsconfig: Option<PathBuf>,
+  deps: bool,
+  jsx_source: bool,
+  help: bool,
+  options: SWCTransformOptions,
+}
+
+fn parse_args(mut argv: impl Iterator<Item = String>) -> Result<Args, String> {
+  let mut args = Args::default();
+  while let Some(arg) = argv.next() {
+    let mut value = |name: &str| argv.next().ok_or(format!("Missing value for {}", name));
+    match arg.as_str() {
+      "-h" | "--help" => {
+        args.help = true;
+        return Ok(args);
+      }
+      "-o" | "--out-dir" => args.out_dir = Some(PathBuf::from(value(&arg)?)),
+      "--root" => args.root = Some(PathBuf::from(value(&arg)?)),
+      "--import-map" => args.import_map = Some(PathBuf::from(value(&arg)?)),
+      "--tsconfig" => args.tsconfig = Some(PathBuf::from(value(&arg)?)),
+      "--target" => args.options.target = Some(value(&arg)?),
+      "--lang" => args.options.lang = Some(value(&arg)?),
+      "--jsx-import-source" => args.options.jsx_import_source = Some(value(&arg)?),
+      "--jsx-runtime" => args.options.jsx_runtime = Some(value(&arg)?),
+      "--jsx-precompile-skip" => {
+        args.options.jsx_precompile_skip_elements = Some(value(&arg)?.split(',').map(|name| name.trim().to_owned()).collect())
+      }
+      "--jsx-factory" => args.options.jsx_factory = Some(value(&arg)?),
+      "--jsx-fragment" => args.options.jsx_fragment_factory = Some(value(&arg)?),
+      "--solid-ssr" => {
+        args.options.solid = Some(SolidOptions {
+          generate: Some(SolidGenerate::Ssr),
+          ..Default::default()
+        })
+      }
+      "--decorators" => args.options.decorators = Some(value(&arg)?),
+      "--emit-decorator-metadata" => args.options.emit_decorator_metadata = Some(true),
+      "--define" => {
+        let define = value(&arg)?;
+        let (key, value) = define.split_once('=').ok_or(format!("Invalid define: {}", define))?;
+        args
+          .options
+          .define
+          .get_or_insert_with(IndexMap::new)
+          .insert(key.to_owned(), value.to_owned());
+      }
+      "--env" => {
+        let env = value(&arg)?;
+        let (key, value) = env.split_once('=').ok_or(format!("Invalid env: {}", env))?;
+        args
+          .options
+          .env
+          .get_or_insert_with(IndexMap::new)
+          .insert(key.to_owned(), serde_json::Value::String(value.to_owned()));
+      }
+      "--polyfills" => args.options.polyfills = Some(true),
+      "--external-helpers" => args.options.external_helpers = Some(true),
+      "--minify" => args.options.minify = Some(Minify::Bool(true)),
+      "--tree-shaking" => args.options.tree_shaking = Some(true),
+      "--used-exports" => {
+        let names = value(&arg)?;
+        args.options.used_exports = Some(if names == "none" {
+          UsedExports::Keyword(names)
+        } else {
+          UsedExports::Names(names.split(',').map(|name| name.trim().to_owned()).collect())
+        });
+      }
+      "--source-map" => args.options.source_map = Some(value(&arg)?),
+      "--dev" => {
+        args.options.dev.get_or_insert_with(DevOptions::default);
+      }
+      "--hmr" => {
+        let runtime = value(&arg)?;
+        args.options.dev.get_or_insert_with(DevOptions::default).hmr = Some(HmrOptions { runtime });
+      }
+      "--refresh" => {
+        let runtime = value(&arg)?;
+        args.options.dev.get_or_insert_with(DevOptions::default).refresh = Some(RefreshOptions { runtime });
+      }
+      "--prefresh" => {
+        let runtime = value(&arg)?;
+        args.options.dev.get_or_insert_with(DevOptions::default).prefresh = Some(RefreshOptions { runtime });
+      }
+      "--jsx-source" => {
+        args.options.dev.get_or_insert_with(DevOptions::default);
+        args.jsx_source = true;
+      }
+      "--deps" => args.deps = true,
+      _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+      _ if args.input.is_some() => return Err(format!("Unexpected argument: {}", arg)),
+      _ => args.input = Some(PathBuf::from(arg)),
+    }
+  }
+  if args.input.is_none() {
+    return Err("Missing input file or directory".to_owned());
+  }
+  // the code is printed to stdout without the output directory, there is no file to write the source map to
+  if args.out_dir.is_none() && !args.deps && args.options.source_map.as_deref() == Some("external") {
+    return Err("The --out-dir option is required for the external source map".to_owned());
+  }
+  Ok(args)
+}
+
+/// Collect the modules in the directory recursively, the `.d.ts` files are ignored.
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+  let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+  entries.sort_by_key(|entry| entry.file_name());
+  for entry in entries {
+    let path = entry.path();
+    let name = entry.file_name().to_string_lossy().into_owned();
+    if name.starts_with('.') || name == "node_modules" {
+      continue;
+    }
+    if path.is_dir() {
+      walk_dir(&path, files)?;
+    } else if !name.ends_with(".d.ts")
+      && path
+        .extension()
+        .is_some_and(|ext| EXTENSIONS.contains(&ext.to_string_lossy().as_ref()))
+    {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+/// Get the module filename, which is the path relative to the root directory with a leading `/`.
+fn module_filename(path: &Path, root: &Path) -> String {
+  match path.strip_prefix(root) {
+    Ok(rel) => "/".to_owned() + rel.to_string_lossy().replace('\\', "/").as_str(),
+    Err(_) => path.to_string_lossy().replace('\\', "/"),
+  }
+}
+
+/// Get the extension of the output file, the JSX files keep the `.jsx` extension if the JSX is preserved.
+fn output_extension(path: &Path, jsx_runtime: &str) -> &'static str {
+  match path.extension().map(|ext| ext.to_string_lossy()).as_deref() {
+    Some("mjs") | Some("mts") => "mjs",
+    Some("jsx") | Some("tsx") if jsx_runtime == "preserve" => "jsx",
+    _ => "js",
+  }
+}
+
+fn load_import_map(path: &Path, root: &Path) -> Result<CompiledImportMap, String> {
+  let path = path
+    .canonicalize()
+    .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+  let json = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+  let mut import_map_raw: serde_json::Value =
+    serde_json::from_str(&json).map_err(|e| format!("Could not parse {}: {}", path.display(), e))?;
+  if let Some(obj) = import_map_raw.as_object_mut() {
+    obj
+      .entry("$src")
+      .or_insert_with(|| serde_json::Value::String(module_filename(&path, root)));
+  }
+  let import_map = CompiledImportMap::parse(import_map_raw)?;
+  for diagnostic in import_map.diagnostics() {
+    eprintln!("warning: {}", diagnostic);
+  }
+  Ok(import_map)
+}
+
+fn load_tsconfig(path: &Path) -> Result<serde_json::Value, String> {
+  let json = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+  serde_json::from_str(&json).map_err(|e| format!("Could not parse {}: {}", path.display(), e))
+}
+
+fn run(mut args: Args) -> Result<bool, String> {
+  let cwd = env::current_dir().map_err(|e| e.to_string())?;
+  let root = args.root.as_deref().unwrap_or(&cwd);
+  let root = root
+    .canonicalize()
+    .map_err(|e| format!("Could not read {}: {}", root.display(), e))?;
+  let input = args.input.as_deref().unwrap();
+  let input = input
+    .canonicalize()
+    .map_err(|e| format!("Could not read {}: {}", input.display(), e))?;
+  let import_map = match &args.import_map {
+    Some(path) => Some(load_import_map(path, &root)?),
+    None => None,
+  };
+  if let Some(path) = &args.tsconfig {
+    args.options.tsconfig = Some(load_tsconfig(path)?);
+  }
+  let (base_dir, files) = if input.is_dir() {
+    if args.out_dir.is_none() && !args.deps {
+      return Err("The --out-dir option is required for a directory input".to_owned());
+    }
+    let mut files = vec![];
+    walk_dir(&input, &mut files).map_err(|e| format!("Could not read {}: {}", input.display(), e))?;
+    (input.clone(), files)
+  } else {
+    (input.parent().unwrap().to_path_buf(), vec![input.clone()])
+  };
+
+  let mut ok = true;
+  let mut deps = IndexMap::new();
+  let mut out_paths: IndexMap<PathBuf, &PathBuf> = IndexMap::new();
+  // share the swc globals between the modules
+  swc_common::GLOBALS.set(&swc_common::Globals::new(), || {
+    for path in &files {
+      let filename = module_filename(path, &root);
+      let code = match fs::read(path) {
+        Ok(code) => code,
+        Err(e) => {
+          eprintln!("error: Could not read {}: {}", path.display(), e);
+          ok = false;
+          continue;
+        }
+      };
+      let mut options = SWCTransformOptions {
+        filename: filename.clone(),
+        code,
+        ..args.options.clone()
+      };
+      if args.jsx_source
+        && let Some(dev) = options.dev.as_mut()
+      {
+        dev.jsx_source = Some(JsxSourceOptions {
+          file_name: path.to_string_lossy().into_owned(),
+        });
+      }
+      let ret = match &import_map {
+        Some(import_map) => tsx::transform_with_import_map(&options, import_map),
+        None => tsx::transform(&options),
+      };
+      let output = match ret {
+        Ok(output) => output,
+        Err(err) => {
+          for diagnostic in &err.diagnostics {
+            eprintln!("error: {}", diagnostic);
+            if let Some(code_frame) = &diagnostic.code_frame {
+              eprintln!("{}", code_frame);
+            }
+          }
+          if err.diagnostics.is_empty() {
+            eprintln!("error: {}: {}", filename, err);
+          }
+          ok = false;
+          continue;
+        }
+      };
+      for warning in &output.warnings {
+        eprintln!("warning: {}", warning);
+      }
+      if args.deps {
+        deps.insert(filename, output.deps);
+        continue;
+      }
+      let Some(out_dir) = &args.out_dir else {
+        print!("{}", String::from_utf8_lossy(&output.code));
+        continue;
+      };
+      let out_path = out_dir
+        .join(path.strip_prefix(&base_dir).unwrap())
+        .with_extension(output_extension(path, &output.jsx_runtime));
+      // e.g. `app.ts` and `app.tsx` are both compiled to `app.js`
+      if let Some(other) = out_paths.get(&out_path) {
+        eprintln!(
+          "error: {} and {} are both written to {}",
+          other.display(),
+          path.display(),
+          out_path.display()
+        );
+        ok = false;
+        continue;
+      }
+      out_paths.insert(out_path.clone(), path);
+      if let Err(e) = write_output(&out_path, output.code, output.map) {
+        eprintln!("error: Could not write {}: {}", out_path.display(), e);
+        ok = false;
+      }
+    }
+  });
+
+  if args.deps {
+    println!("{}", serde_json::to_string_pretty(&deps).unwrap());
+  }
+  Ok(ok)
+}
+
+fn write_output(out_path: &Path, mut code: Vec<u8>, map: Option<Vec<u8>>) -> std::io::Result<()> {
+  if let Some(parent) = out_path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  if let Some(map) = map {
+    let map_path = PathBuf::from(format!("{}.map", out_path.display()));
+    let map_name = map_path.file_name().unwrap().to_string_lossy().into_owned();
+    code.extend_from_slice(format!("\n//# sourceMappingURL={}", map_name).as_bytes());
+    fs::write(&map_path, map)?;
+  }
+  fs::write(out_path, code)
+}
+
+fn main() -> ExitCode {
+  let args = match parse_args(env::args().skip(1)) {
+    Ok(args) if args.help => {
+      print!("{}", USAGE);
+      return ExitCode::SUCCESS;
+    }
+    Ok(args) => args,
+    Err(e) => {
+      eprintln!("error: {}\n\n{}", e, USAGE);
+      return ExitCode::FAILURE;
+    }
+  };
+  match run(args) {
+    Ok(true) => ExitCode::SUCCESS,
+    Ok(false) => ExitCode::FAILURE,
+    Err(e) => {
+      eprintln!("error: {}", e);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn parse(argv: &[&str]) -> Result<Args, String> {
+    parse_args(argv.iter().map(|arg| arg.to_string()))
+  }
+
+  #[test]
+  fn args() {
+    let args = parse(&[
+      "src",
+      "-o",
+      "dist",
+      "--target",
+      "es2020",
+      "--jsx-runtime",
+      "preserve",
+      "--jsx-precompile-skip",
+      "a, b",
+      "--define",
+      "process.env.NODE_ENV=\"production\"",
+      "--define",
+      "DEBUG=false",
+      "--env",
+      "API_URL=https://example.com/?a=1",
+      "--used-exports",
+      "foo, bar",
+      "--solid-ssr",
+      "--deps",
+    ])
+    .unwrap();
+    assert_eq!(args.input, Some(PathBuf::from("src")));
+    assert_eq!(args.out_dir, Some(PathBuf::from("dist")));
+    assert!(args.deps);
+    let options = args.options;
+    assert_eq!(options.target.as_deref(), Some("es2020"));
+    assert_eq!(options.jsx_runtime.as_deref(), Some("preserve"));
+    assert_eq!(options.jsx_precompile_skip_elements, Some(vec!["a".to_owned(), "b".to_owned()]));
+    let define = options.define.unwrap();
+    assert_eq!(define.get("process.env.NODE_ENV").map(String::as_str), Some("\"production\""));
+    assert_eq!(define.get("DEBUG").map(String::as_str), Some("false"));
+    // only the first `=` separates the key and the value
+    assert_eq!(
+      options.env.unwrap().get("API_URL"),
+      Some(&serde_json::Value::String("https://example.com/?a=1".to_owned()))
+    );
+    assert!(matches!(options.used_exports, Some(UsedExports::Names(names)) if names == ["foo", "bar"]));
+    assert_eq!(options.solid.unwrap().generate, Some(SolidGenerate::Ssr));
+    assert!(options.dev.is_none());
+
+    let args = parse(&["app.tsx", "--used-exports", "none", "--hmr", "/hmr.js", "--jsx-source"]).unwrap();
+    assert!(matches!(args.options.used_exports, Some(UsedExports::Keyword(keyword)) if keyword == "none"));
+    assert_eq!(args.options.dev.unwrap().hmr.unwrap().runtime, "/hmr.js");
+    assert!(args.jsx_source);
+  }
+
+  #[test]
+  fn invalid_args() {
+    assert_eq!(parse(&[]).err().unwrap(), "Missing input file or directory");
+    assert_eq!(parse(&["app.ts", "--target"]).err().unwrap(), "Missing value for --target");
+    assert_eq!(parse(&["app.ts", "--define", "DEBUG"]).err().unwrap(), "Invalid define: DEBUG");
+    assert_eq!(parse(&["app.ts", "--env", "MODE"]).err().unwrap(), "Invalid env: MODE");
+    assert_eq!(parse(&["app.ts", "--watch"]).err().unwrap(), "Unknown option: --watch");
+    assert_eq!(parse(&["app.ts", "main.ts"]).err().unwrap(), "Unexpected argument: main.ts");
+    assert_eq!(
+      parse(&["app.ts", "--source-map", "external"]).err().unwrap(),
+      "The --out-dir option is required for the external source map"
+    );
+    assert!(parse(&["app.ts", "--source-map", "external", "-o", "dist"]).is_ok());
+    assert!(parse(&["app.ts", "--source-map", "inline"]).is_ok());
+  }
+
+  #[test]
+  fn help() {
+    assert!(parse(&["-h"]).unwrap().help);
+    assert!(parse(&["app.ts", "--help", "--unknown"]).unwrap().help);
+    assert!(!parse(&["app.ts"]).unwrap().help);
+  }
+
+  #[test]
+  fn module_filenames() {
+    let root = Path::new("/home/user/app");
+    assert_eq!(module_filename(Path::new("/home/user/app/src/app.tsx"), root), "/src/app.tsx");
+    assert_eq!(module_filename(Path::new("/home/user/lib/util.ts"), root), "/home/user/lib/util.ts");
+  }
+
+  #[test]
+  fn output_extensions() {
+    assert_eq!(output_extension(Path::new("app.ts"), "automatic"), "js");
+    assert_eq!(output_extension(Path::new("app.mts"), "automatic"), "mjs");
+    assert_eq!(output_extension(Path::new("app.mjs"), "automatic"), "mjs");
+    assert_eq!(output_extension(Path::new("app.tsx"), "automatic"), "js");
+    assert_eq!(output_extension(Path::new("app.tsx"), "preserve"), "jsx");
+    assert_eq!(output_extension(Path::new("app.jsx"), "preserve"), "jsx");
+    // no JSX in the TypeScript files
+    assert_eq!(output_extension(Path::new("app.ts"), "preserve"), "js");
+  }
+
+  #[test]
+  fn walk_dirs() {
+    let dir = env::temp_dir().join(format!("tsx-walk-dir-{}", std::process::id()));
+    for file in [
+      "app.tsx",
+      "main.ts",
+      "types.d.ts",
+      "style.css",
+      "lib/util.mjs",
+      ".cache/app.js",
+      "node_modules/react/index.js",
+    ] {
+      let path = dir.join(file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(&path, "").unwrap();
+    }
+    let mut files = vec![];
+    walk_dir(&dir, &mut files).unwrap();
+    let files = files.iter().map(|path| module_filename(path, &dir)).collect::<Vec<_>>();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(files, ["/app.tsx", "/lib/util.mjs", "/main.ts"]);
+  }
+
+  #[test]
+  fn out_path_collisions() {
+    let dir = env::temp_dir().join(format!("tsx-out-paths-{}", std::process::id()));
+    let src = dir.join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(src.join("app.ts"), "export const a: number = 1;").unwrap();
+    fs::write(src.join("app.tsx"), "export default () => <div />;").unwrap();
+    fs::write(src.join("main.ts"), "export const b: string = \"b\";").unwrap();
+    let args = Args {
+      input: Some(src.clone()),
+      out_dir: Some(dir.join("dist")),
+      root: Some(dir.clone()),
+      options: SWCTransformOptions {
+        source_map: Some("external".to_owned()),
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let ok = run(args);
+    let app = fs::read_to_string(dir.join("dist/app.js"));
+    let main = fs::read_to_string(dir.join("dist/main.js"));
+    let main_map = dir.join("dist/main.js.map").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    // `app.tsx` is not written over `app.js` of `app.ts`
+    assert_eq!(ok, Ok(false));
+    assert!(app.unwrap().starts_with("export const a = 1;"));
+    assert!(main.unwrap().ends_with("//# sourceMappingURL=main.js.map"));
+    assert!(main_map);
+  }
+}
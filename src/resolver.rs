@@ -1,39 +1,139 @@
-use crate::specifier::{is_abspath_specifier, is_http_specifier, is_relpath_specifier};
+use crate::error::DiagnosticMessage;
+use crate::specifier::{is_abspath_specifier, is_bare_specifier, is_http_specifier, is_relpath_specifier};
 use import_map::ImportMap;
+use indexmap::IndexMap;
 use path_slash::PathBufExt;
 use pathdiff::diff_paths;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::str::FromStr;
 use url::Url;
 
+/// The kind of an import/export statement that introduces a dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencyKind {
+  /// `import ... from "specifier"`
+  Static,
+  /// `import("specifier")`
+  Dynamic,
+  /// `export { ... } from "specifier"` or `export * as ns from "specifier"`
+  ReExport,
+  /// `export * from "specifier"`
+  ExportAll,
+}
+
+/// The byte range of a dependency statement in the raw input, e.g. the offsets of a UTF-16 input count the bytes of UTF-16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DependencySpan {
+  pub start: u32,
+  pub end: u32,
+}
+
+/// A dependency of the module.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyDescriptor {
+  /// the original specifier in the source code.
+  pub specifier: String,
+  /// the resolved URL.
+  pub resolved_url: String,
+  /// the kind of the import/export statement.
+  pub kind: DependencyKind,
+  /// the import attributes, e.g. `with { type: "json" }`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub attributes: Option<IndexMap<String, String>>,
+  /// the source span of the import/export statement, `None` for generated imports.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub span: Option<DependencySpan>,
+}
+
 /// A Resolver to resolve esm import/export URL.
 pub struct Resolver {
   /// the text specifier associated with the import/export statement.
   pub filename: String,
   /// a ordered dependencies of the module
-  pub deps: Vec<(String, String)>,
+  pub deps: Vec<DependencyDescriptor>,
   /// the import map
-  pub import_map: Option<ImportMap>,
+  pub import_map: Option<Rc<ImportMap>>,
+  /// the path aliases of the tsconfig `paths`, e.g. `("@/*", ["/src/*"])`
+  pub path_aliases: Vec<(String, Vec<String>)>,
+  /// the non-fatal issues found while resolving, e.g. unresolved bare specifiers
+  pub warnings: Vec<DiagnosticMessage>,
 }
 
 impl Resolver {
   /// Create a new Resolver.
-  pub fn new(specifier: &str, import_map: Option<ImportMap>) -> Self {
+  pub fn new(specifier: &str, import_map: Option<Rc<ImportMap>>) -> Self {
     Resolver {
       filename: specifier.into(),
       deps: Vec::new(),
       import_map,
+      path_aliases: Vec::new(),
+      warnings: Vec::new(),
     }
   }
 
-  /// Resolve module specifier to a URL.
-  pub fn resolve(&mut self, specifier: &str, with_type: Option<String>) -> String {
-    let referrer = if is_http_specifier(&self.filename) {
+  /// Resolve the specifier with the path aliases, the pattern with the longest prefix wins like TypeScript.
+  fn resolve_path_alias(&self, specifier: &str) -> Option<String> {
+    let mut matched: Option<(usize, String)> = None;
+    for (pattern, targets) in &self.path_aliases {
+      let Some(target) = targets.first() else {
+        continue;
+      };
+      let resolved = match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+          if specifier.len() < prefix.len() + suffix.len() || !specifier.starts_with(prefix) || !specifier.ends_with(suffix) {
+            continue;
+          }
+          if matched.as_ref().is_some_and(|(len, _)| *len >= prefix.len()) {
+            continue;
+          }
+          let star = &specifier[prefix.len()..specifier.len() - suffix.len()];
+          (prefix.len(), target.replacen('*', star, 1))
+        }
+        None if pattern == specifier => return Some(target.clone()),
+        None => continue,
+      };
+      matched = Some(resolved);
+    }
+    matched.map(|(_, path)| path)
+  }
+
+  fn referrer(&self) -> Url {
+    if is_http_specifier(&self.filename) {
       Url::from_str(self.filename.as_str()).unwrap()
     } else {
       Url::from_str(&("file://".to_owned() + self.filename.as_str())).unwrap()
-    };
-    let resolved_url = if let Some(import_map) = &self.import_map {
+    }
+  }
+
+  /// Check if the specifier is a bare specifier that can not be resolved by the import map.
+  pub fn is_unresolved_bare_specifier(&self, specifier: &str) -> bool {
+    match &self.import_map {
+      Some(import_map) => {
+        is_bare_specifier(specifier)
+          && self.resolve_path_alias(specifier).is_none()
+          && import_map.resolve(specifier, &self.referrer()).is_err()
+      }
+      None => false,
+    }
+  }
+
+  /// Resolve module specifier to a URL.
+  pub fn resolve(
+    &mut self,
+    specifier: &str,
+    kind: DependencyKind,
+    attributes: Option<IndexMap<String, String>>,
+    span: Option<DependencySpan>,
+  ) -> String {
+    let with_type = attributes.as_ref().and_then(|attrs| attrs.get("type").cloned());
+    let referrer = self.referrer();
+    let resolved_url = if let Some(path) = self.resolve_path_alias(specifier) {
+      "file://".to_owned() + path.as_str()
+    } else if let Some(import_map) = &self.import_map {
       if let Ok(ret) = import_map.resolve(specifier, &referrer) {
         ret.to_string()
       } else {
@@ -47,7 +147,7 @@ impl Resolver {
       if !is_http_specifier(&self.filename) {
         let mut buf = PathBuf::from(self.filename.to_owned());
         buf.pop();
-        let path = diff_paths(&pathname, buf).unwrap().to_slash().unwrap().to_string();
+        let path = diff_paths(pathname, buf).unwrap().to_slash().unwrap().to_string();
         let rel_path = if !path.starts_with("./") && !path.starts_with("../") {
           "./".to_owned() + path.as_str()
         } else {
@@ -87,14 +187,13 @@ impl Resolver {
       }
     }
 
-    if is_filepath && !flag_raw_url {
-      if let Some(ext) = Path::new(&resolved_url).extension() {
-        if ext.to_str().unwrap_or_default() == "css" {
-          if with_type.unwrap_or_default() != "css" {
-            extra_query = Some("module".to_owned());
-          }
-        }
-      }
+    if is_filepath
+      && !flag_raw_url
+      && let Some(ext) = Path::new(&resolved_url).extension()
+      && ext.to_str().unwrap_or_default() == "css"
+      && with_type.unwrap_or_default() != "css"
+    {
+      extra_query = Some("module".to_owned());
     }
 
     if raw_query.as_ref().is_some() || extra_query.as_ref().is_some() {
@@ -111,7 +210,13 @@ impl Resolver {
     }
 
     // update the dep graph
-    self.deps.push((specifier.to_owned(), resolved_url.clone()));
+    self.deps.push(DependencyDescriptor {
+      specifier: specifier.to_owned(),
+      resolved_url: resolved_url.clone(),
+      kind,
+      attributes,
+      span,
+    });
 
     resolved_url
   }
@@ -1,11 +1,15 @@
 pub fn is_http_specifier(url: &str) -> bool {
-  return url.starts_with("https://") || url.starts_with("http://");
+  url.starts_with("https://") || url.starts_with("http://")
 }
 
 pub fn is_relpath_specifier(specifier: &str) -> bool {
-  return specifier.starts_with("./") || specifier.starts_with("../");
+  specifier.starts_with("./") || specifier.starts_with("../")
 }
 
 pub fn is_abspath_specifier(specifier: &str) -> bool {
-  return specifier.starts_with("/") || specifier.starts_with("file://");
+  specifier.starts_with("/") || specifier.starts_with("file://")
+}
+
+pub fn is_bare_specifier(specifier: &str) -> bool {
+  !is_relpath_specifier(specifier) && !is_abspath_specifier(specifier) && !specifier.contains(':')
 }
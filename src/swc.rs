@@ -1,13 +1,24 @@
-use crate::dev::{Dev, DevOptions};
-use crate::error::{DiagnosticBuffer, ErrorBuffer};
-use crate::import_analyzer::ImportAnalyzer;
+use crate::dev::{Dev, DevOptions, ImportMetaEnv};
+use crate::error::{DiagnosticBuffer, DiagnosticMessage, ErrorBuffer};
+use crate::import_analyzer::{HelperImportResolver, ImportAnalyzer};
 use crate::resolver::Resolver;
 use crate::specifier::is_http_specifier;
+use crate::swc_compat::{Features, compat};
+use crate::swc_define::{Define, define};
+use crate::swc_jsx_precompile::jsx_precompile;
 use crate::swc_jsx_src::jsx_source;
+use crate::swc_minify::{MinifyOptions, minify};
+use crate::swc_polyfill::polyfill_imports;
 use crate::swc_prefresh::swc_prefresh;
+use crate::swc_solid_jsx::{SolidOptions, is_solid_import_source, solid_jsx};
+use crate::swc_tree_shaking::{prune_exports, tree_shaking};
+use crate::swc_vue_jsx::{is_vue_import_source, vue_hmr, vue_jsx};
+use crate::targets::Browsers;
 use base64::{Engine as _, engine::general_purpose};
 use bytes_str::BytesStr;
-use std::cell::RefCell;
+use indexmap::IndexMap;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
 use std::rc::Rc;
@@ -16,24 +27,96 @@ use swc_common::comments::SingleThreadedComments;
 use swc_common::errors::{Handler, HandlerFlags};
 use swc_common::pass::Optional;
 use swc_common::source_map::{SourceMap, SourceMapGenConfig};
-use swc_common::{FileName, Globals, Mark};
-use swc_ecma_transforms::optimization::simplify::dce;
-use swc_ecma_transforms::proposals::decorators;
+use swc_common::{BytePos, FileName, Globals, Mark};
+use swc_ecma_transforms::proposals::{decorator_2022_03::decorator_2022_03, decorators};
 use swc_ecma_transforms::typescript::{tsx, typescript};
 use swc_ecma_transforms::{fixer, helpers, hygiene, react};
-use swc_ecmascript::ast::{EsVersion, Module, Pass, Program};
+use swc_ecmascript::ast::{EsVersion, Ident, Module, Pass, Program};
 use swc_ecmascript::codegen::{Config, Emitter, Node, text_writer::JsWriter};
 use swc_ecmascript::parser::{EsSyntax, Parser, StringInput, Syntax, TsSyntax, lexer};
-use swc_ecmascript::visit::fold_pass;
+use swc_ecmascript::visit::{Visit, VisitWith, fold_pass, noop_visit_type, visit_mut_pass};
+
+/// The decorators proposal that the decorators are transformed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecoratorsVersion {
+  /// The TypeScript `experimentalDecorators`.
+  Legacy,
+  /// The TC39 standard decorators with the `accessor` fields and the `Symbol.metadata`,
+  /// swc transforms them with the 2022-03 version of the proposal, so the later semantic changes,
+  /// e.g. the `addInitializer()` of the field decorators, are not applied.
+  V202311,
+  /// Keep the decorators untransformed.
+  None,
+}
+
+/// The runtime that the JSX is transformed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsxRuntime {
+  /// `jsx()` calls imported from the `<jsx_import_source>/jsx-runtime`.
+  Automatic,
+  /// `React.createElement()` calls, or the calls of the `jsx_factory`.
+  Classic,
+  /// Keep the JSX as is for the downstream JSX compilers.
+  Preserve,
+  /// The static JSX is serialized to the HTML string templates for the server rendering,
+  /// `jsxTemplate()` calls imported from the `<jsx_import_source>/jsx-runtime`.
+  Precompile,
+}
+
+impl JsxRuntime {
+  /// The name of the runtime, the same as the `jsxRuntime` option.
+  pub fn name(&self) -> &'static str {
+    match self {
+      JsxRuntime::Automatic => "automatic",
+      JsxRuntime::Classic => "classic",
+      JsxRuntime::Preserve => "preserve",
+      JsxRuntime::Precompile => "precompile",
+    }
+  }
+}
 
 /// Options for transpiling a module.
 pub struct EmitOptions {
   pub source_map: Option<String>,
   pub dev: Option<DevOptions>,
   pub target: EsVersion,
+  /// the browsers that are resolved from a browserslist query or a User-Agent, overrides the `target`.
+  pub browsers: Option<Browsers>,
+  /// replace the global expressions with the values at compile time, e.g. `process.env.NODE_ENV`.
+  pub define: Vec<Define>,
+  /// the variables of `import.meta.env`, the unknown variables are replaced with `undefined` if it's set.
+  pub env: Option<IndexMap<String, serde_json::Value>>,
+  /// import the helpers from `@swc/helpers` instead of inlining them.
+  pub external_helpers: bool,
+  /// import the `core-js` polyfills of the APIs that are used by the module and not supported by the target.
+  pub polyfills: bool,
+  /// the jsx runtime, default is `Automatic` if the `jsx_import_source` is set, otherwise `Classic`.
+  pub jsx_runtime: Option<JsxRuntime>,
+  /// the jsx import source of the automatic and precompile runtime, default is `react` for the automatic runtime,
+  /// the precompile runtime requires it since react does not export the `jsxTemplate()`.
   pub jsx_import_source: Option<String>,
-  pub minify: bool,
+  /// the classic runtime pragma, default is `React.createElement`.
+  pub jsx_factory: Option<String>,
+  /// the classic runtime fragment pragma, default is `React.Fragment`.
+  pub jsx_fragment_factory: Option<String>,
+  /// the elements that are not serialized to the templates by the precompile runtime, e.g. `["a", "img"]`.
+  pub jsx_precompile_skip_elements: Vec<String>,
+  /// the options of the Solid JSX transform that is used when the jsx import source is `solid-js`.
+  pub solid: SolidOptions,
+  /// the decorators version, default is `Legacy` for TypeScript and `V202311` for JavaScript.
+  pub decorators: Option<DecoratorsVersion>,
+  /// emit the `design:type`, `design:paramtypes` and `design:returntype` metadata for the legacy decorators of TypeScript.
+  pub emit_decorator_metadata: bool,
+  /// use the `[[Define]]` semantics for the class fields, the `useDefineForClassFields` of tsconfig.
+  /// The public class fields of TypeScript are lowered to assignments if it's disabled, the private fields are kept.
+  pub use_define_for_class_fields: bool,
+  /// keep the imports/exports that are not marked as `type`, the `verbatimModuleSyntax` of tsconfig.
+  pub verbatim_module_syntax: bool,
+  /// compress and mangle the code with the minifier, and remove the whitespace when emitting.
+  pub minify: Option<MinifyOptions>,
   pub tree_shaking: bool,
+  /// the export names that are imported by the other modules, the other exports are dropped and the code they leave is stripped.
+  pub used_exports: Option<Vec<String>>,
 }
 
 impl Default for EmitOptions {
@@ -42,13 +125,36 @@ impl Default for EmitOptions {
       source_map: None,
       dev: None,
       target: EsVersion::Es2022,
+      browsers: None,
+      define: vec![],
+      env: None,
+      external_helpers: false,
+      polyfills: false,
+      jsx_runtime: None,
       jsx_import_source: None,
-      minify: false,
+      jsx_factory: None,
+      jsx_fragment_factory: None,
+      jsx_precompile_skip_elements: vec![],
+      solid: SolidOptions::default(),
+      decorators: None,
+      emit_decorator_metadata: false,
+      use_define_for_class_fields: true,
+      verbatim_module_syntax: false,
+      minify: None,
       tree_shaking: false,
+      used_exports: None,
     }
   }
 }
 
+/// The output of transpiling a module.
+pub struct EmitOutput {
+  pub code: Vec<u8>,
+  pub map: Option<Vec<u8>>,
+  /// the syntax features of the module that are lowered for the target.
+  pub lowered_features: Features,
+}
+
 #[derive(Debug)]
 pub struct EmitError {
   pub message: String,
@@ -60,22 +166,44 @@ impl fmt::Display for EmitError {
   }
 }
 
-pub struct SourceMapGenOptions {}
+pub struct SourceMapGenOptions {
+  /// the original names of the identifiers, the names of the minified code are mangled.
+  names: HashMap<BytePos, Atom>,
+}
 
 impl SourceMapGenConfig for SourceMapGenOptions {
   fn file_name_to_source(&self, f: &FileName) -> String {
     f.to_string()
   }
+  fn name_for_bytepos(&self, pos: BytePos) -> Option<&str> {
+    self.names.get(&pos).map(|name| name.as_str())
+  }
   fn inline_sources_content(&self, f: &FileName) -> bool {
     f.is_real()
   }
 }
 
+/// The JSX pragmas in the comments of a module, e.g. `/** @jsxImportSource preact */`.
+#[derive(Debug, Default, Clone)]
+pub struct JsxPragmas {
+  /// `@jsxRuntime classic|automatic`
+  pub runtime: Option<String>,
+  /// `@jsxImportSource <source>`
+  pub import_source: Option<String>,
+  /// `@jsx <factory>`
+  pub factory: Option<String>,
+  /// `@jsxFrag <factory>`
+  pub fragment_factory: Option<String>,
+}
+
+#[allow(clippy::upper_case_acronyms)]
 pub struct SWC {
   syntax: Syntax,
   module: Module,
   comments: SingleThreadedComments,
   source_map: Rc<SourceMap>,
+  /// the syntax errors that the parser recovered from.
+  pub recovered_errors: Vec<DiagnosticMessage>,
 }
 
 impl SWC {
@@ -99,31 +227,86 @@ impl SWC {
         ..HandlerFlags::default()
       },
     );
-    let sm = &source_map;
-    let module = Parser::new_from(lexer).parse_module().map_err(move |err| {
-      let mut diagnostic = err.into_diagnostic(&handler);
-      diagnostic.emit();
-      DiagnosticBuffer::from_error_buffer(error_buffer, |span| sm.lookup_char_pos(span.lo))
-    })?;
+    let mut parser = Parser::new_from(lexer);
+    let result = parser.parse_module();
+    // the parser collects recoverable errors and continues parsing
+    let recovered_errors = parser.take_errors();
+    let module = match result {
+      Ok(module) => module,
+      Err(err) => {
+        for err in recovered_errors {
+          err.into_diagnostic(&handler).emit();
+        }
+        err.into_diagnostic(&handler).emit();
+        return Err(DiagnosticBuffer::from_error_buffer(error_buffer, &source_map));
+      }
+    };
+    for err in recovered_errors {
+      err.into_diagnostic(&handler).emit();
+    }
+    let recovered_errors = DiagnosticBuffer::from_error_buffer(error_buffer, &source_map).0;
 
     Ok(SWC {
       syntax,
       module,
       comments,
       source_map: Rc::new(source_map),
+      recovered_errors,
     })
   }
 
+  /// Read the JSX pragmas from the comments, the first one wins if a pragma is specified more than once.
+  pub fn jsx_pragmas(&self) -> JsxPragmas {
+    let mut pragmas = JsxPragmas::default();
+    let (leading, trailing) = self.comments.borrow_all();
+    let mut comments = leading.values().chain(trailing.values()).flatten().collect::<Vec<_>>();
+    comments.sort_by_key(|comment| comment.span.lo);
+    for comment in comments {
+      let mut words = comment.text.split_whitespace().map(|word| word.trim_start_matches('*'));
+      while let Some(word) = words.next() {
+        let pragma = match word {
+          "@jsxRuntime" => &mut pragmas.runtime,
+          "@jsxImportSource" => &mut pragmas.import_source,
+          "@jsx" => &mut pragmas.factory,
+          "@jsxFrag" => &mut pragmas.fragment_factory,
+          _ => continue,
+        };
+        if let Some(value) = words.next().filter(|value| !value.is_empty())
+          && pragma.is_none()
+        {
+          *pragma = Some(value.to_owned());
+        }
+      }
+    }
+    pragmas
+  }
+
   /// Transform the module to JavaScript and optionally generate a source map.
-  pub fn transform(self, resolver: Rc<RefCell<Resolver>>, options: &EmitOptions) -> Result<(Vec<u8>, Option<Vec<u8>>), EmitError> {
-    swc_common::GLOBALS.set(&Globals::new(), || {
-      let pass = self.build_pass(resolver.clone(), options);
-      let (code, map) = self.emit(pass, options)?;
-      Ok((code, map))
+  pub fn transform(self, resolver: Rc<RefCell<Resolver>>, options: &EmitOptions) -> Result<EmitOutput, EmitError> {
+    if options.jsx_runtime == Some(JsxRuntime::Precompile) && options.jsx_import_source.is_none() {
+      return Err(EmitError {
+        message: "The precompile jsxRuntime requires the jsxImportSource, e.g. \"preact\"".to_owned(),
+      });
+    }
+    let lowered = Rc::new(Cell::new(Features::default()));
+    // reuse the globals if they are set by the caller, e.g. `transformMany`
+    let (code, map) = if swc_common::GLOBALS.is_set() {
+      let pass = self.build_pass(resolver.clone(), options, lowered.clone());
+      self.emit(pass, options)?
+    } else {
+      swc_common::GLOBALS.set(&Globals::new(), || {
+        let pass = self.build_pass(resolver.clone(), options, lowered.clone());
+        self.emit(pass, options)
+      })?
+    };
+    Ok(EmitOutput {
+      code,
+      map,
+      lowered_features: lowered.get(),
     })
   }
 
-  fn build_pass<'a>(&'a self, resolver: Rc<RefCell<Resolver>>, options: &EmitOptions) -> impl Pass + 'a {
+  fn build_pass<'a>(&'a self, resolver: Rc<RefCell<Resolver>>, options: &EmitOptions, lowered: Rc<Cell<Features>>) -> impl Pass + 'a {
     let top_level_mark = Mark::new();
     let unresolved_mark = Mark::new();
     let specifier = resolver.borrow().filename.clone();
@@ -131,36 +314,67 @@ impl SWC {
     let is_tsx = if let Syntax::Typescript(ts) = self.syntax { ts.tsx } else { false };
     let is_jsx = if let Syntax::Es(es) = self.syntax { es.jsx } else { false };
     let is_http_sepcifier = is_http_specifier(&specifier);
+    let decorators_version = options.decorators.unwrap_or(if is_ts || is_tsx {
+      DecoratorsVersion::Legacy
+    } else {
+      DecoratorsVersion::V202311
+    });
     let is_dev = options.dev.is_some();
     let dev_options = options.dev.clone().unwrap_or_default();
-    let jsx_options = if let Some(jsx_import_source) = &options.jsx_import_source {
-      react::Options {
+    let jsx_runtime = options.jsx_runtime.unwrap_or(if options.jsx_import_source.is_some() {
+      JsxRuntime::Automatic
+    } else {
+      JsxRuntime::Classic
+    });
+    let jsx_options = match jsx_runtime {
+      JsxRuntime::Automatic | JsxRuntime::Precompile => react::Options {
         runtime: Some(react::Runtime::Automatic),
-        import_source: Some(Atom::from(jsx_import_source.as_str())),
+        import_source: options.jsx_import_source.as_deref().map(Atom::from),
         development: Some(is_dev),
         ..Default::default()
-      }
-    } else {
-      react::Options {
+      },
+      JsxRuntime::Classic | JsxRuntime::Preserve => react::Options {
+        runtime: Some(react::Runtime::Classic),
+        pragma: options.jsx_factory.as_deref().map(BytesStr::from_str_slice),
+        pragma_frag: options.jsx_fragment_factory.as_deref().map(BytesStr::from_str_slice),
         development: Some(is_dev),
         ..Default::default()
-      }
+      },
+    };
+    let is_solid = jsx_runtime == JsxRuntime::Automatic && options.jsx_import_source.as_deref().is_some_and(is_solid_import_source);
+    let is_vue = jsx_runtime == JsxRuntime::Automatic && options.jsx_import_source.as_deref().is_some_and(is_vue_import_source);
+    let is_refresh = is_dev && !is_http_sepcifier && (dev_options.refresh.is_some() || dev_options.prefresh.is_some());
+    // the refresh runtime checks the names of the components, e.g. `isLikelyComponentType`
+    let minify_options = options.minify.clone().map(|minify_options| MinifyOptions {
+      keep_class_names: if is_refresh { Some(true) } else { minify_options.keep_class_names },
+      keep_fn_names: if is_refresh { Some(true) } else { minify_options.keep_fn_names },
+      ..minify_options
+    });
+    let ts_config = typescript::Config {
+      verbatim_module_syntax: options.verbatim_module_syntax,
+      native_class_properties: options.use_define_for_class_fields,
+      ..Default::default()
     };
 
     // https://github.com/swc-project/swc/pull/9680
     (
-      swc_ecma_transforms::resolver(unresolved_mark, top_level_mark, is_ts),
-      // todo: support the new decorators proposal
-      decorators::decorators(decorators::Config {
-        legacy: true,
-        emit_metadata: false,
-        use_define_for_class_fields: false,
-      }),
-      Optional::new(typescript::strip(unresolved_mark, top_level_mark), is_ts),
+      swc_ecma_transforms::resolver(unresolved_mark, top_level_mark, is_ts || is_tsx),
+      Optional::new(
+        decorators::decorators(decorators::Config {
+          legacy: true,
+          emit_metadata: options.emit_decorator_metadata && (is_ts || is_tsx),
+          use_define_for_class_fields: options.use_define_for_class_fields,
+        }),
+        decorators_version == DecoratorsVersion::Legacy,
+      ),
+      // swc only provides the 2022-03 transform, its runtime helper supports the `context.metadata` of the 2023-11 version
+      Optional::new(decorator_2022_03(), decorators_version == DecoratorsVersion::V202311),
+      Optional::new(typescript::typescript(ts_config, unresolved_mark, top_level_mark), is_ts),
       Optional::new(
         tsx(
           self.source_map.clone(),
-          typescript::Config::default(),
+          ts_config,
+          // keep the imports of the classic runtime pragma
           typescript::TsxConfig {
             pragma: jsx_options.pragma.clone(),
             pragma_frag: jsx_options.pragma_frag.clone(),
@@ -171,63 +385,139 @@ impl SWC {
         ),
         is_tsx,
       ),
-      // jsx features passes
+      Optional::new(define(options.define.clone(), unresolved_mark), !options.define.is_empty()),
+      // jsx passes
       Optional::new(
         (
           Optional::new(
-            jsx_source(
-              self.source_map.clone(),
-              dev_options.jsx_source.as_ref().map(|opts| opts.file_name.clone()),
+            (
+              Optional::new(
+                jsx_source(
+                  self.source_map.clone(),
+                  dev_options.jsx_source.as_ref().map(|opts| opts.file_name.clone()),
+                ),
+                dev_options.jsx_source.is_some(),
+              ),
+              react::jsx_self(is_dev),
+              react::jsx(
+                self.source_map.clone(),
+                Some(&self.comments),
+                jsx_options,
+                top_level_mark,
+                unresolved_mark,
+              ),
+              react::display_name(),
+              react::pure_annotations(Some(&self.comments)),
             ),
-            dev_options.jsx_source.is_some(),
+            !matches!(jsx_runtime, JsxRuntime::Preserve | JsxRuntime::Precompile) && !is_solid && !is_vue,
           ),
-          react::jsx_self(is_dev),
-          react::jsx(
-            self.source_map.clone(),
-            Some(&self.comments),
-            jsx_options,
-            top_level_mark,
-            unresolved_mark,
+          Optional::new(
+            jsx_precompile(
+              options.jsx_import_source.clone().unwrap_or_default(),
+              options.jsx_precompile_skip_elements.clone(),
+            ),
+            jsx_runtime == JsxRuntime::Precompile,
+          ),
+          Optional::new(
+            solid_jsx(
+              options.jsx_import_source.clone().unwrap_or_default() + "/web",
+              options.solid.clone(),
+            ),
+            is_solid,
           ),
-          react::display_name(),
-          react::pure_annotations(Some(&self.comments)),
+          Optional::new(vue_jsx(options.jsx_import_source.clone().unwrap_or_default()), is_vue),
         ),
         is_jsx || is_tsx,
       ),
+      // register the `defineComponent` exports before the `vue` imports are resolved
+      Optional::new(vue_hmr(&specifier), is_vue && dev_options.hmr.is_some() && !is_http_sepcifier),
+      // inject the polyfill imports before the imports are resolved
+      Optional::new(
+        polyfill_imports(options.target, options.browsers.clone(), unresolved_mark),
+        options.polyfills,
+      ),
       // analyze imports
       fold_pass(ImportAnalyzer {
         resolver: resolver.clone(),
+        source_map: self.source_map.clone(),
       }),
-      // dev mode
-      Optional::new(
-        (
-          Optional::new(
-            react::refresh(
-              true,
-              Some(react::RefreshOptions {
-                refresh_reg: "$RefreshReg$".into(),
-                refresh_sig: "$RefreshSig$".into(),
-                emit_full_signatures: false,
-              }),
-              self.source_map.clone(),
-              Some(&self.comments),
-              top_level_mark,
+      (
+        // replace the `import.meta.env.*` with the literals
+        visit_mut_pass(ImportMetaEnv {
+          resolver: resolver.clone(),
+          source_map: self.source_map.clone(),
+          env: options.env.clone(),
+          dev: is_dev,
+        }),
+        // dev mode
+        Optional::new(
+          (
+            Optional::new(
+              react::refresh(
+                true,
+                Some(react::RefreshOptions {
+                  refresh_reg: "$RefreshReg$".into(),
+                  refresh_sig: "$RefreshSig$".into(),
+                  emit_full_signatures: false,
+                }),
+                self.source_map.clone(),
+                Some(&self.comments),
+                top_level_mark,
+              ),
+              dev_options.refresh.is_some() || dev_options.prefresh.is_some(),
             ),
-            dev_options.refresh.is_some() || dev_options.prefresh.is_some(),
+            Optional::new(swc_prefresh(&specifier), dev_options.prefresh.is_some()),
+            fold_pass(Dev {
+              resolver: resolver.clone(),
+              options: options.dev.clone().unwrap_or_default(),
+            }),
           ),
-          Optional::new(swc_prefresh(&specifier), dev_options.prefresh.is_some()),
-          fold_pass(Dev {
-            resolver: resolver.clone(),
-            options: options.dev.clone().unwrap_or_default(),
-          }),
+          is_dev && !is_http_sepcifier,
         ),
-        is_dev && !is_http_sepcifier,
+      ),
+      // lower the syntax that is newer than the target
+      compat(
+        options
+          .browsers
+          .as_ref()
+          .map_or_else(|| Features::newer_than(options.target), Browsers::features),
+        (is_ts || is_tsx) && !options.use_define_for_class_fields,
+        unresolved_mark,
+        lowered,
       ),
       // optimization passes
       (
         fixer::paren_remover(Some(&self.comments)),
         helpers::inject_helpers(top_level_mark),
-        Optional::new(dce::dce(Default::default(), unresolved_mark), options.tree_shaking),
+        Optional::new(
+          visit_mut_pass(HelperImportResolver {
+            analyzer: ImportAnalyzer {
+              resolver: resolver.clone(),
+              source_map: self.source_map.clone(),
+            },
+          }),
+          options.external_helpers,
+        ),
+        Optional::new(
+          tree_shaking(unresolved_mark),
+          options.tree_shaking && options.used_exports.is_none(),
+        ),
+        // drop the unused exports, then strip the code they leave with the tree shaking passes
+        Optional::new(
+          prune_exports(options.used_exports.clone().unwrap_or_default(), unresolved_mark, resolver.clone()),
+          options.used_exports.is_some(),
+        ),
+        Optional::new(
+          minify(
+            minify_options.unwrap_or_default(),
+            options.target,
+            self.source_map.clone(),
+            &self.comments,
+            unresolved_mark,
+            top_level_mark,
+          ),
+          options.minify.is_some(),
+        ),
         hygiene::hygiene_with_config(hygiene::Config {
           top_level_mark,
           ..Default::default()
@@ -239,7 +529,11 @@ impl SWC {
 
   fn emit<P: Pass>(&self, pass: P, options: &EmitOptions) -> Result<(Vec<u8>, Option<Vec<u8>>), EmitError> {
     let program = Program::Module(self.module.clone());
-    let program = helpers::HELPERS.set(&helpers::Helpers::new(false), || program.apply(pass));
+    let mut names = HashMap::new();
+    if options.source_map.is_some() && options.minify.is_some() {
+      program.visit_with(&mut IdentNames(&mut names));
+    }
+    let program = helpers::HELPERS.set(&helpers::Helpers::new(options.external_helpers), || program.apply(pass));
     let mut js_buf = Vec::new();
     let mut mappings = Vec::new();
     let writer = if options.source_map.is_some() {
@@ -248,7 +542,7 @@ impl SWC {
       JsWriter::new(self.source_map.clone(), "\n", &mut js_buf, None)
     };
     let mut emitter = Emitter {
-      cfg: Config::default().with_target(options.target).with_minify(options.minify),
+      cfg: Config::default().with_target(options.target).with_minify(options.minify.is_some()),
       comments: Some(&self.comments),
       cm: self.source_map.clone(),
       wr: writer,
@@ -262,7 +556,7 @@ impl SWC {
       let mut source_map_json = Vec::new();
       if let Err(error) = self
         .source_map
-        .build_source_map(&mut mappings, None, SourceMapGenOptions {})
+        .build_source_map(&mappings, None, SourceMapGenOptions { names })
         .to_writer(&mut source_map_json)
       {
         return Err(EmitError {
@@ -283,6 +577,17 @@ impl SWC {
   }
 }
 
+/// Collect the names of the identifiers by the positions.
+struct IdentNames<'a>(&'a mut HashMap<BytePos, Atom>);
+
+impl Visit for IdentNames<'_> {
+  noop_visit_type!();
+
+  fn visit_ident(&mut self, ident: &Ident) {
+    self.0.insert(ident.span.lo, ident.sym.clone());
+  }
+}
+
 fn get_es_syntax(jsx: bool) -> EsSyntax {
   EsSyntax {
     fn_bind: true,
@@ -291,6 +596,8 @@ fn get_es_syntax(jsx: bool) -> EsSyntax {
     allow_super_outside_method: true,
     allow_return_outside_function: true,
     decorators: true,
+    decorators_before_export: true,
+    auto_accessors: true,
     jsx,
     ..EsSyntax::default()
   }
@@ -307,11 +614,11 @@ fn get_ts_syntax(tsx: bool) -> TsSyntax {
 fn get_syntax(filename: &str, lang: Option<String>) -> Syntax {
   let lang = lang.unwrap_or(
     filename
-      .split(|c| c == '?' || c == '#')
+      .split(['?', '#'])
       .next()
       .unwrap()
       .split('.')
-      .last()
+      .next_back()
       .unwrap_or("js")
       .to_lowercase(),
   );
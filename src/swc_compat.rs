@@ -0,0 +1,464 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use swc_common::Mark;
+use swc_common::pass::Optional;
+use swc_common::{DUMMY_SP, Spanned};
+use swc_ecma_transforms::compat;
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::constructor::inject_after_super;
+use swc_ecmascript::utils::{ExprFactory, default_constructor_with_span, is_literal, prop_name_to_member_prop};
+use swc_ecmascript::visit::{Visit, VisitMut, VisitMutWith, VisitWith, noop_visit_mut_type, noop_visit_type, visit_mut_pass};
+
+/// A syntax feature that can be lowered for the older runtimes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+  UnicodeSetsRegex,
+  ClassStaticBlock,
+  PrivatePropertyInObject,
+  ClassProperties,
+  RegexpHasIndices,
+  LogicalAssignments,
+  OptionalChaining,
+  NullishCoalescing,
+  ExportNamespaceFrom,
+  OptionalCatchBinding,
+  ObjectRestSpread,
+  RegexpDotAll,
+  RegexpNamedGroups,
+  RegexpLookbehind,
+  RegexpUnicodeProperty,
+  AsyncGenerators,
+  AsyncToGenerator,
+  Exponentiation,
+}
+
+impl Feature {
+  /// All the features, ordered from the newest to the oldest.
+  pub const ALL: [Feature; 18] = [
+    Feature::UnicodeSetsRegex,
+    Feature::ClassStaticBlock,
+    Feature::PrivatePropertyInObject,
+    Feature::ClassProperties,
+    Feature::RegexpHasIndices,
+    Feature::LogicalAssignments,
+    Feature::OptionalChaining,
+    Feature::NullishCoalescing,
+    Feature::ExportNamespaceFrom,
+    Feature::OptionalCatchBinding,
+    Feature::ObjectRestSpread,
+    Feature::RegexpDotAll,
+    Feature::RegexpNamedGroups,
+    Feature::RegexpLookbehind,
+    Feature::RegexpUnicodeProperty,
+    Feature::AsyncGenerators,
+    Feature::AsyncToGenerator,
+    Feature::Exponentiation,
+  ];
+
+  /// The name of the feature, same as the babel plugin without the `transform-` prefix.
+  pub fn name(self) -> &'static str {
+    match self {
+      Feature::UnicodeSetsRegex => "unicode-sets-regex",
+      Feature::ClassStaticBlock => "class-static-block",
+      Feature::PrivatePropertyInObject => "private-property-in-object",
+      Feature::ClassProperties => "class-properties",
+      Feature::RegexpHasIndices => "regexp-has-indices",
+      Feature::LogicalAssignments => "logical-assignment-operators",
+      Feature::OptionalChaining => "optional-chaining",
+      Feature::NullishCoalescing => "nullish-coalescing-operator",
+      Feature::ExportNamespaceFrom => "export-namespace-from",
+      Feature::OptionalCatchBinding => "optional-catch-binding",
+      Feature::ObjectRestSpread => "object-rest-spread",
+      Feature::RegexpDotAll => "dotall-regex",
+      Feature::RegexpNamedGroups => "named-capturing-groups-regex",
+      Feature::RegexpLookbehind => "regexp-lookbehind",
+      Feature::RegexpUnicodeProperty => "unicode-property-regex",
+      Feature::AsyncGenerators => "async-generator-functions",
+      Feature::AsyncToGenerator => "async-to-generator",
+      Feature::Exponentiation => "exponentiation-operator",
+    }
+  }
+
+  /// The ECMAScript version that the feature is introduced in.
+  pub fn es_version(self) -> EsVersion {
+    match self {
+      Feature::UnicodeSetsRegex => EsVersion::Es2024,
+      Feature::ClassStaticBlock | Feature::PrivatePropertyInObject | Feature::ClassProperties | Feature::RegexpHasIndices => {
+        EsVersion::Es2022
+      }
+      Feature::LogicalAssignments => EsVersion::Es2021,
+      Feature::OptionalChaining | Feature::NullishCoalescing | Feature::ExportNamespaceFrom => EsVersion::Es2020,
+      Feature::OptionalCatchBinding => EsVersion::Es2019,
+      Feature::ObjectRestSpread
+      | Feature::RegexpDotAll
+      | Feature::RegexpNamedGroups
+      | Feature::RegexpLookbehind
+      | Feature::RegexpUnicodeProperty
+      | Feature::AsyncGenerators => EsVersion::Es2018,
+      Feature::AsyncToGenerator => EsVersion::Es2017,
+      Feature::Exponentiation => EsVersion::Es2016,
+    }
+  }
+}
+
+/// A set of features.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Features(u32);
+
+impl Features {
+  /// The features that are newer than the `target`.
+  pub fn newer_than(target: EsVersion) -> Self {
+    Feature::ALL.into_iter().filter(|f| f.es_version() > target).collect()
+  }
+
+  pub fn contains(&self, feature: Feature) -> bool {
+    self.0 & (1 << feature as u32) != 0
+  }
+
+  pub fn insert(&mut self, feature: Feature) {
+    self.0 |= 1 << feature as u32;
+  }
+
+  pub fn intersection(&self, other: Features) -> Features {
+    Features(self.0 & other.0)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = Feature> + '_ {
+    Feature::ALL.into_iter().filter(|f| self.contains(*f))
+  }
+
+  /// The newest ECMAScript version that has none of the features, used by the code generator.
+  pub fn es_version(&self) -> EsVersion {
+    match self.iter().map(|f| f.es_version()).min() {
+      Some(EsVersion::Es2024) => EsVersion::Es2023,
+      Some(EsVersion::Es2022) => EsVersion::Es2021,
+      Some(EsVersion::Es2021) => EsVersion::Es2020,
+      Some(EsVersion::Es2020) => EsVersion::Es2019,
+      Some(EsVersion::Es2019) => EsVersion::Es2018,
+      Some(EsVersion::Es2018) => EsVersion::Es2017,
+      Some(EsVersion::Es2017) => EsVersion::Es2016,
+      Some(_) => EsVersion::Es2015,
+      None => EsVersion::EsNext,
+    }
+  }
+}
+
+impl FromIterator<Feature> for Features {
+  fn from_iter<I: IntoIterator<Item = Feature>>(iter: I) -> Self {
+    let mut features = Features::default();
+    for feature in iter {
+      features.insert(feature);
+    }
+    features
+  }
+}
+
+/// Lower the syntax `features`, e.g. the optional chaining is transformed for `es2019`.
+/// The features that are used by the module are recorded to the `lowered`.
+/// The `set_public_fields` uses the `[[Set]]` semantics for the public class fields like the `useDefineForClassFields: false` of TypeScript.
+pub fn compat(features: Features, set_public_fields: bool, unresolved_mark: Mark, lowered: Rc<Cell<Features>>) -> impl Pass {
+  let f = |feature| features.contains(feature);
+  (
+    visit_mut_pass(Detector {
+      features,
+      lowered: lowered.clone(),
+    }),
+    Optional::new(visit_mut_pass(SetPublicFields), set_public_fields),
+    Optional::new(
+      compat::regexp::regexp(compat::regexp::Config {
+        unicode_sets_regex: f(Feature::UnicodeSetsRegex),
+        has_indices: f(Feature::RegexpHasIndices),
+        dot_all_regex: f(Feature::RegexpDotAll),
+        named_capturing_groups_regex: f(Feature::RegexpNamedGroups),
+        lookbehind_assertion: f(Feature::RegexpLookbehind),
+        unicode_property_regex: f(Feature::RegexpUnicodeProperty),
+        ..Default::default()
+      }),
+      f(Feature::UnicodeSetsRegex)
+        || f(Feature::RegexpHasIndices)
+        || f(Feature::RegexpDotAll)
+        || f(Feature::RegexpNamedGroups)
+        || f(Feature::RegexpLookbehind)
+        || f(Feature::RegexpUnicodeProperty),
+    ),
+    Optional::new(compat::es2022::static_blocks(), f(Feature::ClassStaticBlock)),
+    Optional::new(visit_mut_pass(PrivateInObject), f(Feature::PrivatePropertyInObject)),
+    Optional::new(
+      compat::es2022::class_properties(
+        compat::es2022::class_properties::Config {
+          set_public_fields,
+          ..Default::default()
+        },
+        unresolved_mark,
+      ),
+      f(Feature::ClassProperties),
+    ),
+    Optional::new(compat::es2021::logical_assignments(), f(Feature::LogicalAssignments)),
+    (
+      Optional::new(
+        compat::es2020::optional_chaining(Default::default(), unresolved_mark),
+        f(Feature::OptionalChaining),
+      ),
+      Optional::new(
+        compat::es2020::nullish_coalescing(Default::default()),
+        f(Feature::NullishCoalescing),
+      ),
+      Optional::new(compat::es2020::export_namespace_from(), f(Feature::ExportNamespaceFrom)),
+    ),
+    Optional::new(compat::es2019::optional_catch_binding(), f(Feature::OptionalCatchBinding)),
+    Optional::new(compat::es2018::object_rest_spread(Default::default()), f(Feature::ObjectRestSpread)),
+    AsyncFunctions { unresolved_mark, lowered },
+    Optional::new(compat::es2016::exponentiation(), f(Feature::Exponentiation)),
+  )
+}
+
+/// Lower the async functions and the async generators, swc transforms both of them in the async-to-generator pass,
+/// so the async functions of a module that uses the async generators are lowered for `es2017` too.
+struct AsyncFunctions {
+  unresolved_mark: Mark,
+  lowered: Rc<Cell<Features>>,
+}
+
+impl Pass for AsyncFunctions {
+  fn process(&mut self, program: &mut Program) {
+    let lowered = self.lowered.get();
+    if lowered.contains(Feature::AsyncToGenerator) || lowered.contains(Feature::AsyncGenerators) {
+      compat::es2017::async_to_generator(Default::default(), self.unresolved_mark).process(program);
+    }
+  }
+}
+
+/// Use the `[[Set]]` semantics for the public class fields like the `useDefineForClassFields: false` of TypeScript,
+/// the instance fields are assigned in the constructor, the static fields are assigned in the static blocks,
+/// and the fields without the initializer are removed. The private fields are kept as is,
+/// and so are the fields with the computed keys that may have side effects, since the keys are evaluated with the class.
+struct SetPublicFields;
+
+impl VisitMut for SetPublicFields {
+  noop_visit_mut_type!();
+
+  fn visit_mut_class(&mut self, class: &mut Class) {
+    class.visit_mut_children_with(self);
+
+    let mut inits = vec![];
+    let mut members = Vec::with_capacity(class.body.len());
+    for member in class.body.drain(..) {
+      let ClassMember::ClassProp(prop) = member else {
+        members.push(member);
+        continue;
+      };
+      if !prop.decorators.is_empty() || matches!(&prop.key, PropName::Computed(key) if !is_literal(&key.expr)) {
+        members.push(ClassMember::ClassProp(prop));
+        continue;
+      }
+      let Some(value) = prop.value else {
+        continue;
+      };
+      let assign = AssignExpr {
+        span: prop.span,
+        op: AssignOp::Assign,
+        left: MemberExpr {
+          span: prop.key.span(),
+          obj: Box::new(Expr::This(ThisExpr { span: DUMMY_SP })),
+          prop: prop_name_to_member_prop(prop.key),
+        }
+        .into(),
+        right: value,
+      };
+      if prop.is_static {
+        // `this` of the static block is the class
+        members.push(ClassMember::StaticBlock(StaticBlock {
+          span: prop.span,
+          body: BlockStmt {
+            stmts: vec![assign.into_stmt()],
+            ..Default::default()
+          },
+        }));
+      } else {
+        inits.push(Box::new(Expr::Assign(assign)));
+      }
+    }
+    class.body = members;
+
+    if inits.is_empty() {
+      return;
+    }
+    match class.body.iter_mut().find_map(|member| member.as_mut_constructor()) {
+      Some(constructor) => inject_after_super(constructor, inits),
+      None => {
+        let mut constructor = default_constructor_with_span(class.super_class.is_some(), class.span);
+        inject_after_super(&mut constructor, inits);
+        class.body.insert(0, ClassMember::Constructor(constructor));
+      }
+    }
+  }
+}
+
+/// Record the features that are used by the module and lowered by the compat passes.
+struct Detector {
+  features: Features,
+  lowered: Rc<Cell<Features>>,
+}
+
+impl VisitMut for Detector {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module(&mut self, module: &mut Module) {
+    let mut usage = Usage::default();
+    module.visit_with(&mut usage);
+    let mut lowered = usage.0.intersection(self.features);
+    if lowered.contains(Feature::AsyncGenerators) && usage.0.contains(Feature::AsyncToGenerator) {
+      lowered.insert(Feature::AsyncToGenerator);
+    }
+    self.lowered.set(lowered);
+  }
+}
+
+#[derive(Default)]
+struct Usage(Features);
+
+impl Visit for Usage {
+  noop_visit_type!();
+
+  fn visit_static_block(&mut self, block: &StaticBlock) {
+    self.0.insert(Feature::ClassStaticBlock);
+    block.visit_children_with(self);
+  }
+
+  fn visit_class_prop(&mut self, prop: &ClassProp) {
+    self.0.insert(Feature::ClassProperties);
+    prop.visit_children_with(self);
+  }
+
+  fn visit_private_prop(&mut self, prop: &PrivateProp) {
+    self.0.insert(Feature::ClassProperties);
+    prop.visit_children_with(self);
+  }
+
+  fn visit_private_method(&mut self, method: &PrivateMethod) {
+    self.0.insert(Feature::ClassProperties);
+    method.visit_children_with(self);
+  }
+
+  fn visit_bin_expr(&mut self, expr: &BinExpr) {
+    match expr.op {
+      BinaryOp::In if expr.left.is_private_name() => self.0.insert(Feature::PrivatePropertyInObject),
+      BinaryOp::NullishCoalescing => self.0.insert(Feature::NullishCoalescing),
+      BinaryOp::Exp => self.0.insert(Feature::Exponentiation),
+      _ => {}
+    }
+    expr.visit_children_with(self);
+  }
+
+  fn visit_assign_expr(&mut self, expr: &AssignExpr) {
+    match expr.op {
+      AssignOp::AndAssign | AssignOp::OrAssign | AssignOp::NullishAssign => self.0.insert(Feature::LogicalAssignments),
+      AssignOp::ExpAssign => self.0.insert(Feature::Exponentiation),
+      _ => {}
+    }
+    expr.visit_children_with(self);
+  }
+
+  fn visit_opt_chain_expr(&mut self, expr: &OptChainExpr) {
+    self.0.insert(Feature::OptionalChaining);
+    expr.visit_children_with(self);
+  }
+
+  fn visit_export_namespace_specifier(&mut self, _: &ExportNamespaceSpecifier) {
+    self.0.insert(Feature::ExportNamespaceFrom);
+  }
+
+  fn visit_catch_clause(&mut self, clause: &CatchClause) {
+    if clause.param.is_none() {
+      self.0.insert(Feature::OptionalCatchBinding);
+    }
+    clause.visit_children_with(self);
+  }
+
+  fn visit_object_pat(&mut self, pat: &ObjectPat) {
+    if pat.props.iter().any(|prop| matches!(prop, ObjectPatProp::Rest(_))) {
+      self.0.insert(Feature::ObjectRestSpread);
+    }
+    pat.visit_children_with(self);
+  }
+
+  fn visit_object_lit(&mut self, obj: &ObjectLit) {
+    if obj.props.iter().any(|prop| matches!(prop, PropOrSpread::Spread(_))) {
+      self.0.insert(Feature::ObjectRestSpread);
+    }
+    obj.visit_children_with(self);
+  }
+
+  fn visit_function(&mut self, function: &Function) {
+    if function.is_async {
+      self.0.insert(if function.is_generator {
+        Feature::AsyncGenerators
+      } else {
+        Feature::AsyncToGenerator
+      });
+    }
+    function.visit_children_with(self);
+  }
+
+  fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+    if stmt.is_await {
+      self.0.insert(Feature::AsyncGenerators);
+    }
+    stmt.visit_children_with(self);
+  }
+
+  fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+    if arrow.is_async {
+      self.0.insert(Feature::AsyncToGenerator);
+    }
+    arrow.visit_children_with(self);
+  }
+
+  fn visit_regex(&mut self, regex: &Regex) {
+    let (exp, flags) = (regex.exp.as_str(), regex.flags.as_str());
+    if flags.contains('v') {
+      self.0.insert(Feature::UnicodeSetsRegex);
+    }
+    if flags.contains('d') {
+      self.0.insert(Feature::RegexpHasIndices);
+    }
+    if flags.contains('s') {
+      self.0.insert(Feature::RegexpDotAll);
+    }
+    if exp.contains("(?<=") || exp.contains("(?<!") {
+      self.0.insert(Feature::RegexpLookbehind);
+    }
+    if exp
+      .match_indices("(?<")
+      .any(|(i, _)| !matches!(exp.as_bytes().get(i + 3), Some(b'=' | b'!')))
+    {
+      self.0.insert(Feature::RegexpNamedGroups);
+    }
+    if flags.contains('u') && (exp.contains("\\p{") || exp.contains("\\P{")) {
+      self.0.insert(Feature::RegexpUnicodeProperty);
+    }
+  }
+}
+
+/// Run the private-in pass for every module item, the brand check variables of `#x in obj` are declared
+/// in the next statement block after the class instead of the module scope when it runs for the whole module.
+struct PrivateInObject;
+
+impl VisitMut for PrivateInObject {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+    let mut body = Vec::with_capacity(items.len());
+    for item in items.drain(..) {
+      let program = Program::Module(Module {
+        body: vec![item],
+        ..Default::default()
+      });
+      let Program::Module(module) = program.apply(compat::es2022::private_in_object()) else {
+        unreachable!()
+      };
+      body.extend(module.body);
+    }
+    *items = body;
+  }
+}
@@ -0,0 +1,86 @@
+use swc_atoms::Atom;
+use swc_common::{FileName, Mark, SourceMap, Spanned};
+use swc_ecmascript::ast::*;
+use swc_ecmascript::parser::{Parser, StringInput, Syntax, lexer};
+use swc_ecmascript::utils::drop_span;
+use swc_ecmascript::visit::{VisitMut, VisitMutWith, noop_visit_mut_type, visit_mut_pass};
+
+/// A compile-time replacement, e.g. `process.env.NODE_ENV` is replaced with `"production"`.
+#[derive(Clone)]
+pub struct Define {
+  /// the member path, e.g. `["process", "env", "NODE_ENV"]`
+  path: Vec<Atom>,
+  value: Box<Expr>,
+}
+
+impl Define {
+  /// Parse a define entry, the `key` is an identifier or a member path,
+  /// and the `value` is a JavaScript expression, e.g. `"\"production\""`.
+  pub fn parse(key: &str, value: &str) -> Result<Self, String> {
+    let path = key.split('.').map(|part| part.trim()).collect::<Vec<_>>();
+    if path.iter().any(|part| !is_ident(part)) {
+      return Err(format!("Invalid define key \"{}\"", key));
+    }
+    let source_map = SourceMap::default();
+    let source_file = source_map.new_source_file(FileName::Anon.into(), value.trim().to_owned());
+    let lexer = lexer::Lexer::new(Syntax::default(), EsVersion::EsNext, StringInput::from(&*source_file), None);
+    let mut parser = Parser::new_from(lexer);
+    let value = match parser.parse_expr() {
+      // the whole value must be an expression
+      Ok(expr) if parser.take_errors().is_empty() && expr.span_hi() == source_file.end_pos => expr,
+      _ => return Err(format!("Invalid define value of \"{}\": {}", key, value)),
+    };
+    Ok(Define {
+      path: path.into_iter().map(Atom::from).collect(),
+      // the spans of the value are not in the source map of the module
+      value: drop_span(value),
+    })
+  }
+}
+
+fn is_ident(s: &str) -> bool {
+  let mut chars = s.chars();
+  chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$') && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Replace the global identifiers and member expressions with the `defines`, the shadowed names are not replaced.
+pub fn define(defines: Vec<Define>, unresolved_mark: Mark) -> impl Pass {
+  visit_mut_pass(DefineReplacer { defines, unresolved_mark })
+}
+
+struct DefineReplacer {
+  defines: Vec<Define>,
+  unresolved_mark: Mark,
+}
+
+impl DefineReplacer {
+  /// Check if the expression matches the path, e.g. `process.env.NODE_ENV` or `process.env["NODE_ENV"]`.
+  fn is_match(&self, expr: &Expr, path: &[Atom]) -> bool {
+    match (expr, path) {
+      (Expr::Ident(ident), [name]) => ident.sym == *name && ident.ctxt.outer() == self.unresolved_mark,
+      (Expr::Member(MemberExpr { obj, prop, .. }), [rest @ .., name]) if !rest.is_empty() => {
+        let prop_matched = match prop {
+          MemberProp::Ident(ident) => ident.sym == *name,
+          MemberProp::Computed(ComputedPropName { expr, .. }) => {
+            matches!(expr.as_ref(), Expr::Lit(Lit::Str(s)) if s.value == name.as_str())
+          }
+          MemberProp::PrivateName(_) => false,
+        };
+        prop_matched && self.is_match(obj, rest)
+      }
+      _ => false,
+    }
+  }
+}
+
+impl VisitMut for DefineReplacer {
+  noop_visit_mut_type!();
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    if let Some(define) = self.defines.iter().find(|define| self.is_match(expr, &define.path)) {
+      *expr = *define.value.clone();
+      return;
+    }
+    expr.visit_mut_children_with(self);
+  }
+}
@@ -1,6 +1,7 @@
+use indexmap::IndexMap;
 use swc_common::{DUMMY_SP, SyntaxContext};
 use swc_ecmascript::ast::*;
-use swc_ecmascript::utils::quote_ident;
+use swc_ecmascript::utils::{ExprFactory, quote_ident};
 
 pub fn get_object_value<'a>(obj: &'a ObjectLit, key: &str) -> Option<&'a Expr> {
   obj.props.iter().find_map(|prop| match prop {
@@ -9,10 +10,9 @@ pub fn get_object_value<'a>(obj: &'a ObjectLit, key: &str) -> Option<&'a Expr> {
         key: PropName::Ident(ident),
         value,
       }) = kv.as_ref()
+        && ident.sym.eq(key)
       {
-        if ident.sym.eq(key) {
-          return Some(value.as_ref());
-        }
+        return Some(value.as_ref());
       }
       None
     }
@@ -20,6 +20,31 @@ pub fn get_object_value<'a>(obj: &'a ObjectLit, key: &str) -> Option<&'a Expr> {
   })
 }
 
+/// Collect the string-valued properties of an object literal, e.g. `{ type: "json" }`.
+pub fn get_object_str_map(obj: &ObjectLit) -> IndexMap<String, String> {
+  obj
+    .props
+    .iter()
+    .filter_map(|prop| match prop {
+      PropOrSpread::Prop(kv) => match kv.as_ref() {
+        Prop::KeyValue(KeyValueProp { key, value }) => {
+          let key = match key {
+            PropName::Ident(ident) => ident.sym.to_string(),
+            PropName::Str(s) => s.value.to_string_lossy().into_owned(),
+            _ => return None,
+          };
+          match value.as_ref() {
+            Expr::Lit(Lit::Str(s)) => Some((key, s.value.to_string_lossy().into_owned())),
+            _ => None,
+          }
+        }
+        _ => None,
+      },
+      _ => None,
+    })
+    .collect()
+}
+
 pub fn assign_decl(var_name: &str, expr: Expr) -> ModuleItem {
   ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
     span: DUMMY_SP,
@@ -103,3 +128,85 @@ pub fn new_str(s: &str) -> Str {
 pub fn ident_expr(s: &str) -> Expr {
   Expr::Ident(quote_ident!(s).into())
 }
+
+/// Clean the JSX text like React: the lines are trimmed and joined by a space, the blank lines are removed.
+pub fn jsx_text(value: &str) -> Option<String> {
+  let lines = value.lines().collect::<Vec<_>>();
+  let mut text = String::new();
+  for (i, line) in lines.iter().enumerate() {
+    let mut line = line.replace('\t', " ");
+    if i > 0 {
+      line = line.trim_start().to_owned();
+    }
+    if i < lines.len() - 1 {
+      line = line.trim_end().to_owned();
+    }
+    if line.is_empty() {
+      continue;
+    }
+    if !text.is_empty() {
+      text.push(' ');
+    }
+    text.push_str(&line);
+  }
+  if text.is_empty() { None } else { Some(decode_entities(&text)) }
+}
+
+pub fn decode_entities(text: &str) -> String {
+  if !text.contains('&') {
+    return text.to_owned();
+  }
+  text
+    .replace("&nbsp;", "\u{a0}")
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&quot;", "\"")
+    .replace("&#39;", "'")
+    .replace("&amp;", "&")
+}
+
+pub fn jsx_name_to_expr(name: JSXElementName) -> Expr {
+  fn object_to_expr(obj: JSXObject) -> Expr {
+    match obj {
+      JSXObject::Ident(id) => Expr::Ident(id),
+      JSXObject::JSXMemberExpr(member) => object_to_expr(member.obj).make_member(member.prop).into(),
+    }
+  }
+  match name {
+    JSXElementName::Ident(id) => Expr::Ident(id),
+    JSXElementName::JSXMemberExpr(member) => object_to_expr(member.obj).make_member(member.prop).into(),
+    JSXElementName::JSXNamespacedName(ns) => str_expr(&format!("{}:{}", ns.ns.sym, ns.name.sym)),
+  }
+}
+
+pub fn jsx_attr_name(name: &JSXAttrName) -> String {
+  match name {
+    JSXAttrName::Ident(id) => id.sym.to_string(),
+    JSXAttrName::JSXNamespacedName(ns) => format!("{}:{}", ns.ns.sym, ns.name.sym),
+  }
+}
+
+pub fn prop_name(key: &str) -> PropName {
+  let is_ident = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+  if is_ident {
+    PropName::Ident(quote_ident!(key))
+  } else {
+    PropName::Str(key.into())
+  }
+}
+
+pub fn kv_prop(key: &str, value: Expr) -> PropOrSpread {
+  PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+    key: prop_name(key),
+    value: Box::new(value),
+  })))
+}
+
+pub fn object_expr(props: Vec<PropOrSpread>) -> Expr {
+  Expr::Object(ObjectLit { span: DUMMY_SP, props })
+}
+
+pub fn str_expr(s: &str) -> Expr {
+  Expr::Lit(Lit::Str(s.into()))
+}
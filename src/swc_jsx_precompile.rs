@@ -0,0 +1,354 @@
+// the `jsx: "precompile"` transform of Deno, see https://deno.com/blog/v1.38#fastest-jsx-transform
+use crate::swc_helpers::*;
+use indexmap::IndexMap;
+use swc_common::DUMMY_SP;
+use swc_common::util::take::Take;
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::{ExprFactory, private_ident, quote_ident};
+use swc_ecmascript::visit::{VisitMut, VisitMutWith, noop_visit_mut_type, visit_mut_pass};
+
+/// Serialize the static JSX to the HTML string templates, the dynamic parts are passed to the
+/// `jsxTemplate`, `jsxAttr` and `jsxEscape` functions imported from the `<import_source>/jsx-runtime`.
+/// The elements in `skip_elements` are always created by the `jsx` function.
+pub fn jsx_precompile(import_source: String, skip_elements: Vec<String>) -> impl Pass {
+  visit_mut_pass(JsxPrecompile {
+    module_name: import_source + "/jsx-runtime",
+    skip_elements,
+    runtime: IndexMap::new(),
+    templates: Vec::new(),
+  })
+}
+
+const VOID_ELEMENTS: [&str; 14] = [
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+struct JsxPrecompile {
+  module_name: String,
+  skip_elements: Vec<String>,
+  /// the runtime functions used by the module, e.g. `jsxTemplate` -> `_jsxTemplate`
+  runtime: IndexMap<&'static str, Ident>,
+  /// the hoisted templates, e.g. `const $$_tpl_1 = ["<div>", "</div>"]`
+  templates: Vec<VarDeclarator>,
+}
+
+/// The HTML template of a JSX tree, the `parts` are joined by the `values`.
+struct Template {
+  parts: Vec<String>,
+  values: Vec<Expr>,
+}
+
+impl Template {
+  fn push_str(&mut self, s: &str) {
+    self.parts.last_mut().unwrap().push_str(s);
+  }
+
+  fn push_value(&mut self, value: Expr) {
+    self.values.push(value);
+    self.parts.push(String::new());
+  }
+}
+
+impl JsxPrecompile {
+  fn call_runtime(&mut self, name: &'static str, args: Vec<Expr>) -> Expr {
+    let callee: Expr = self
+      .runtime
+      .entry(name)
+      .or_insert_with(|| private_ident!(format!("_{}", name)))
+      .clone()
+      .into();
+    callee.as_call(DUMMY_SP, args.into_iter().map(|arg| arg.as_arg()).collect())
+  }
+
+  /// Check if the element can be serialized to the HTML template.
+  fn is_serializable(&self, el: &JSXElement) -> bool {
+    let JSXElementName::Ident(id) = &el.opening.name else {
+      return false;
+    };
+    if !id.sym.starts_with(|c: char| c.is_ascii_lowercase()) || self.skip_elements.iter().any(|name| id.sym == *name) {
+      return false;
+    }
+    el.opening.attrs.iter().all(|attr| match attr {
+      JSXAttrOrSpread::JSXAttr(attr) => !matches!(jsx_attr_name(&attr.name).as_str(), "key" | "ref" | "dangerouslySetInnerHTML"),
+      JSXAttrOrSpread::SpreadElement(_) => false,
+    })
+  }
+
+  fn transform_element(&mut self, el: JSXElement) -> Expr {
+    if self.is_serializable(&el) {
+      let mut t = Template {
+        parts: vec![String::new()],
+        values: vec![],
+      };
+      self.serialize_element(el, &mut t);
+      return self.template(t);
+    }
+
+    // jsx(type, { ...props, children }, key)
+    let tag = match el.opening.name {
+      JSXElementName::Ident(id) if id.sym.starts_with(|c: char| c.is_ascii_lowercase()) => str_expr(&id.sym),
+      name => jsx_name_to_expr(name),
+    };
+    let mut props = vec![];
+    let mut key = None;
+    for attr in el.opening.attrs {
+      match attr {
+        JSXAttrOrSpread::SpreadElement(spread) => props.push(PropOrSpread::Spread(SpreadElement {
+          dot3_token: DUMMY_SP,
+          expr: self.transform_expr(spread.expr),
+        })),
+        JSXAttrOrSpread::JSXAttr(attr) => {
+          let name = jsx_attr_name(&attr.name);
+          let value = match attr.value {
+            None => Expr::Lit(Lit::Bool(true.into())),
+            Some(JSXAttrValue::Str(s)) => str_expr(&s.value.to_string_lossy()),
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+              expr: JSXExpr::Expr(expr), ..
+            })) => *self.transform_expr(expr),
+            Some(JSXAttrValue::JSXExprContainer(_)) => continue,
+            Some(JSXAttrValue::JSXElement(el)) => self.transform_element(*el),
+            Some(JSXAttrValue::JSXFragment(fragment)) => self.transform_fragment(fragment),
+          };
+          if name == "key" {
+            key = Some(value);
+          } else {
+            props.push(kv_prop(&name, value));
+          }
+        }
+      }
+    }
+    if let Some(children) = self.transform_children(el.children) {
+      props.push(kv_prop("children", children));
+    }
+    let mut args = vec![tag, object_expr(props)];
+    args.extend(key);
+    self.call_runtime("jsx", args)
+  }
+
+  fn transform_fragment(&mut self, fragment: JSXFragment) -> Expr {
+    let mut t = Template {
+      parts: vec![String::new()],
+      values: vec![],
+    };
+    self.serialize_children(fragment.children, &mut t);
+    self.template(t)
+  }
+
+  /// Transform the children of a component to an expression, or an array for the multiple children.
+  fn transform_children(&mut self, children: Vec<JSXElementChild>) -> Option<Expr> {
+    let mut exprs = vec![];
+    for child in children {
+      match child {
+        JSXElementChild::JSXText(text) => {
+          if let Some(text) = jsx_text(&text.value) {
+            exprs.push(str_expr(&text));
+          }
+        }
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::Expr(expr), ..
+        }) => exprs.push(*self.transform_expr(expr)),
+        JSXElementChild::JSXExprContainer(_) => {}
+        JSXElementChild::JSXSpreadChild(spread) => exprs.push(*self.transform_expr(spread.expr)),
+        JSXElementChild::JSXElement(el) => exprs.push(self.transform_element(*el)),
+        JSXElementChild::JSXFragment(fragment) => exprs.push(self.transform_fragment(fragment)),
+      }
+    }
+    match exprs.len() {
+      0 => None,
+      1 => exprs.pop(),
+      _ => Some(Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: exprs.into_iter().map(|expr| Some(expr.as_arg())).collect(),
+      })),
+    }
+  }
+
+  /// `jsxTemplate($$_tpl_1, ...values)`
+  fn template(&mut self, t: Template) -> Expr {
+    let id = private_ident!(format!("$$_tpl_{}", self.templates.len() + 1));
+    self.templates.push(VarDeclarator {
+      span: DUMMY_SP,
+      name: id.clone().into(),
+      init: Some(Box::new(Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: t.parts.iter().map(|part| Some(str_expr(part).as_arg())).collect(),
+      }))),
+      definite: false,
+    });
+    let mut args = vec![Expr::Ident(id)];
+    args.extend(t.values);
+    self.call_runtime("jsxTemplate", args)
+  }
+
+  fn serialize_element(&mut self, el: JSXElement, t: &mut Template) {
+    if !self.is_serializable(&el) {
+      let value = self.transform_element(el);
+      t.push_value(value);
+      return;
+    }
+    let JSXElementName::Ident(id) = &el.opening.name else {
+      unreachable!()
+    };
+    let tag = id.sym.to_string();
+    t.push_str(&format!("<{}", tag));
+    for attr in el.opening.attrs {
+      let JSXAttrOrSpread::JSXAttr(attr) = attr else {
+        continue;
+      };
+      let name = html_attr_name(&jsx_attr_name(&attr.name));
+      match attr.value {
+        None => t.push_str(&format!(" {}", name)),
+        Some(JSXAttrValue::Str(s)) => t.push_str(&format!(" {}=\"{}\"", name, escape_html(&s.value.to_string_lossy(), true))),
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::Expr(expr), ..
+        })) => match &*expr {
+          Expr::Lit(Lit::Str(s)) => t.push_str(&format!(" {}=\"{}\"", name, escape_html(&s.value.to_string_lossy(), true))),
+          Expr::Lit(Lit::Num(n)) => t.push_str(&format!(" {}=\"{}\"", name, n.value)),
+          Expr::Lit(Lit::Bool(Bool { value: true, .. })) => t.push_str(&format!(" {}", name)),
+          Expr::Lit(Lit::Bool(_)) | Expr::Lit(Lit::Null(_)) => {}
+          _ => {
+            // jsxAttr("class", value)
+            let value = *self.transform_expr(expr);
+            t.push_str(" ");
+            let value = self.call_runtime("jsxAttr", vec![str_expr(&name), value]);
+            t.push_value(value);
+          }
+        },
+        Some(JSXAttrValue::JSXExprContainer(_)) => {}
+        Some(JSXAttrValue::JSXElement(el)) => {
+          let value = self.transform_element(*el);
+          t.push_str(" ");
+          let value = self.call_runtime("jsxAttr", vec![str_expr(&name), value]);
+          t.push_value(value);
+        }
+        Some(JSXAttrValue::JSXFragment(fragment)) => {
+          let value = self.transform_fragment(fragment);
+          t.push_str(" ");
+          let value = self.call_runtime("jsxAttr", vec![str_expr(&name), value]);
+          t.push_value(value);
+        }
+      }
+    }
+    t.push_str(">");
+    if VOID_ELEMENTS.contains(&tag.as_str()) {
+      return;
+    }
+    self.serialize_children(el.children, t);
+    t.push_str(&format!("</{}>", tag));
+  }
+
+  fn serialize_children(&mut self, children: Vec<JSXElementChild>, t: &mut Template) {
+    for child in children {
+      match child {
+        JSXElementChild::JSXText(text) => {
+          if let Some(text) = jsx_text(&text.value) {
+            t.push_str(&escape_html(&text, false));
+          }
+        }
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::Expr(expr), ..
+        }) => match *expr {
+          Expr::Lit(Lit::Str(s)) => t.push_str(&escape_html(&s.value.to_string_lossy(), false)),
+          Expr::Lit(Lit::Num(n)) => t.push_str(&n.value.to_string()),
+          Expr::Lit(Lit::Bool(_)) | Expr::Lit(Lit::Null(_)) => {}
+          Expr::JSXElement(el) => self.serialize_element(*el, t),
+          Expr::JSXFragment(fragment) => self.serialize_children(fragment.children, t),
+          expr => {
+            // jsxEscape(value)
+            let value = *self.transform_expr(Box::new(expr));
+            let value = self.call_runtime("jsxEscape", vec![value]);
+            t.push_value(value);
+          }
+        },
+        JSXElementChild::JSXExprContainer(_) => {}
+        JSXElementChild::JSXSpreadChild(spread) => {
+          let value = *self.transform_expr(spread.expr);
+          let value = self.call_runtime("jsxEscape", vec![value]);
+          t.push_value(value);
+        }
+        JSXElementChild::JSXElement(el) => self.serialize_element(*el, t),
+        JSXElementChild::JSXFragment(fragment) => self.serialize_children(fragment.children, t),
+      }
+    }
+  }
+
+  /// Transform the JSX inside of the expression.
+  fn transform_expr(&mut self, mut expr: Box<Expr>) -> Box<Expr> {
+    expr.visit_mut_with(self);
+    expr
+  }
+}
+
+impl VisitMut for JsxPrecompile {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module(&mut self, module: &mut Module) {
+    module.visit_mut_children_with(self);
+    if self.runtime.is_empty() {
+      return;
+    }
+    let mut items = vec![ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+      span: DUMMY_SP,
+      specifiers: self
+        .runtime
+        .iter()
+        .map(|(name, local)| {
+          ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: local.clone(),
+            imported: Some(ModuleExportName::Ident(quote_ident!(*name).into())),
+            is_type_only: false,
+          })
+        })
+        .collect(),
+      src: Box::new(self.module_name.as_str().into()),
+      type_only: false,
+      with: None,
+      phase: Default::default(),
+    }))];
+    if !self.templates.is_empty() {
+      items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        kind: VarDeclKind::Const,
+        decls: std::mem::take(&mut self.templates),
+        ..Default::default()
+      })))));
+    }
+    let pos = module
+      .body
+      .iter()
+      .position(|item| !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+      .unwrap_or(module.body.len());
+    module.body.splice(pos..pos, items);
+  }
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    match expr {
+      Expr::JSXElement(_) => {
+        let Expr::JSXElement(el) = expr.take() else { unreachable!() };
+        *expr = self.transform_element(*el);
+      }
+      Expr::JSXFragment(_) => {
+        let Expr::JSXFragment(fragment) = expr.take() else { unreachable!() };
+        *expr = self.transform_fragment(fragment);
+      }
+      _ => expr.visit_mut_children_with(self),
+    }
+  }
+}
+
+/// Get the HTML attribute name of a JSX attribute, e.g. `className` -> `class`.
+fn html_attr_name(name: &str) -> String {
+  match name {
+    "className" => "class".to_owned(),
+    "htmlFor" => "for".to_owned(),
+    "acceptCharset" => "accept-charset".to_owned(),
+    "httpEquiv" => "http-equiv".to_owned(),
+    "xlinkHref" => "xlink:href".to_owned(),
+    _ => name.to_owned(),
+  }
+}
+
+fn escape_html(text: &str, attr: bool) -> String {
+  let text = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+  if attr { text.replace('"', "&quot;") } else { text }
+}
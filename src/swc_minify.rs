@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use std::rc::Rc;
+use swc_common::comments::{Comments, SingleThreadedComments};
+use swc_common::{Mark, SourceMap};
+use swc_core::ecma::minifier::optimize;
+use swc_core::ecma::minifier::option::{CompressOptions, ExtraOptions, MangleOptions, MinifyOptions as SwcMinifyOptions};
+use swc_ecmascript::ast::*;
+
+/// Options for the minifier, `minify: true` enables all of them except the `keep*` options.
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MinifyOptions {
+  /// drop the dead code and simplify the expressions, default is enabled.
+  pub compress: Option<bool>,
+  /// shorten the local names, default is enabled.
+  pub mangle: Option<bool>,
+  /// keep the class names, default is disabled.
+  pub keep_class_names: Option<bool>,
+  /// keep the function names, default is disabled.
+  pub keep_fn_names: Option<bool>,
+}
+
+/// The `minify` option, a boolean or the minifier options.
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Minify {
+  Bool(bool),
+  Options(MinifyOptions),
+}
+
+impl Minify {
+  /// The minifier options, `None` if the minifier is disabled.
+  pub fn options(&self) -> Option<MinifyOptions> {
+    match self {
+      Minify::Bool(true) => Some(MinifyOptions::default()),
+      Minify::Bool(false) => None,
+      Minify::Options(options) => Some(options.clone()),
+    }
+  }
+}
+
+/// Compress and mangle the module with the swc minifier, the spans are kept for the source map.
+pub fn minify(
+  options: MinifyOptions,
+  target: EsVersion,
+  source_map: Rc<SourceMap>,
+  comments: &SingleThreadedComments,
+  unresolved_mark: Mark,
+  top_level_mark: Mark,
+) -> impl Pass + '_ {
+  Minifier {
+    options,
+    target,
+    source_map,
+    comments,
+    unresolved_mark,
+    top_level_mark,
+  }
+}
+
+struct Minifier<'a> {
+  options: MinifyOptions,
+  target: EsVersion,
+  source_map: Rc<SourceMap>,
+  comments: &'a SingleThreadedComments,
+  unresolved_mark: Mark,
+  top_level_mark: Mark,
+}
+
+impl Pass for Minifier<'_> {
+  fn process(&mut self, program: &mut Program) {
+    let keep_class_names = self.options.keep_class_names.unwrap_or_default();
+    let keep_fn_names = self.options.keep_fn_names.unwrap_or_default();
+    let compress = self.options.compress.unwrap_or(true).then(|| CompressOptions {
+      ecma: self.target,
+      module: true,
+      keep_classnames: keep_class_names,
+      keep_fnames: keep_fn_names,
+      ..Default::default()
+    });
+    #[allow(deprecated)]
+    let mangle = self.options.mangle.unwrap_or(true).then(|| MangleOptions {
+      props: None,
+      // the top-level names of a module are not visible to the other modules
+      top_level: Some(true),
+      keep_class_names,
+      keep_fn_names,
+      keep_private_props: false,
+      ie8: false,
+      safari10: false,
+      reserved: vec![],
+      eval: false,
+      disable_char_freq: false,
+    });
+    let options = SwcMinifyOptions {
+      compress,
+      mangle,
+      ..Default::default()
+    };
+    let extra = ExtraOptions {
+      unresolved_mark: self.unresolved_mark,
+      top_level_mark: self.top_level_mark,
+      mangle_name_cache: None,
+    };
+    let input = std::mem::replace(program, Program::Module(Module::default()));
+    *program = optimize(
+      input,
+      self.source_map.clone(),
+      Some(self.comments as &dyn Comments),
+      None,
+      &options,
+      &extra,
+    );
+  }
+}
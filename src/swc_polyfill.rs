@@ -0,0 +1,259 @@
+use crate::swc_helpers::new_str;
+use crate::targets::{Browsers, Version};
+use indexmap::IndexSet;
+use swc_common::{DUMMY_SP, Mark};
+use swc_ecmascript::ast::*;
+use swc_ecmascript::visit::{Visit, VisitMut, VisitWith, noop_visit_mut_type, noop_visit_type, visit_mut_pass};
+
+/// The API to detect.
+enum Api {
+  /// a global, e.g. `structuredClone`
+  Global(&'static str),
+  /// a static method, e.g. `Promise.withResolvers`
+  Static(&'static str, &'static str),
+  /// a prototype method, any call of the method is matched since the type of the object is unknown, e.g. `arr.findLast()`
+  Method(&'static str),
+  /// an array method with a generic name, only the calls on the array literals are matched, e.g. `[1, 2].at(-1)`
+  ArrayMethod(&'static str),
+  /// a string method with a generic name, only the calls on the string literals are matched, e.g. `"abc".at(-1)`
+  StringMethod(&'static str),
+}
+
+/// A polyfill of the `core-js` modules.
+struct Polyfill {
+  api: Api,
+  module: &'static str,
+  es_version: EsVersion,
+  /// the first versions of the browsers that support the API, in the order of chrome, edge, firefox, safari, opera, ios, samsung and node.
+  support: [Version; 8],
+}
+
+const fn polyfill(api: Api, module: &'static str, es_version: EsVersion, support: [Version; 8]) -> Polyfill {
+  Polyfill {
+    api,
+    module,
+    es_version,
+    support,
+  }
+}
+
+const TO_REVERSED: [Version; 8] = [(110, 0), (110, 0), (115, 0), (16, 0), (96, 0), (16, 0), (21, 0), (20, 0)];
+const FIND_LAST: [Version; 8] = [(97, 0), (97, 0), (104, 0), (15, 4), (83, 0), (15, 4), (18, 0), (18, 0)];
+const GROUP_BY: [Version; 8] = [(117, 0), (117, 0), (119, 0), (17, 4), (103, 0), (17, 4), (24, 0), (21, 0)];
+const WELL_FORMED: [Version; 8] = [(111, 0), (111, 0), (119, 0), (16, 4), (97, 0), (16, 4), (22, 0), (20, 0)];
+const FLAT: [Version; 8] = [(69, 0), (79, 0), (62, 0), (12, 0), (56, 0), (12, 0), (10, 0), (11, 0)];
+const AT: [Version; 8] = [(92, 0), (92, 0), (90, 0), (15, 4), (78, 0), (15, 4), (16, 0), (16, 6)];
+
+/// The polyfills, the data is from `core-js-compat`.
+const POLYFILLS: [Polyfill; 22] = [
+  polyfill(
+    Api::Static("Array", "fromAsync"),
+    "es.array.from-async",
+    EsVersion::EsNext,
+    [(121, 0), (121, 0), (115, 0), (16, 4), (107, 0), (16, 4), (25, 0), (22, 0)],
+  ),
+  polyfill(
+    Api::Static("Promise", "withResolvers"),
+    "es.promise.with-resolvers",
+    EsVersion::Es2024,
+    [(119, 0), (119, 0), (121, 0), (17, 4), (105, 0), (17, 4), (25, 0), (22, 0)],
+  ),
+  polyfill(Api::Static("Object", "groupBy"), "es.object.group-by", EsVersion::Es2024, GROUP_BY),
+  polyfill(Api::Static("Map", "groupBy"), "es.map.group-by", EsVersion::Es2024, GROUP_BY),
+  polyfill(
+    Api::Method("isWellFormed"),
+    "es.string.is-well-formed",
+    EsVersion::Es2024,
+    WELL_FORMED,
+  ),
+  polyfill(
+    Api::Method("toWellFormed"),
+    "es.string.to-well-formed",
+    EsVersion::Es2024,
+    WELL_FORMED,
+  ),
+  polyfill(Api::Method("toReversed"), "es.array.to-reversed", EsVersion::Es2023, TO_REVERSED),
+  polyfill(Api::Method("toSorted"), "es.array.to-sorted", EsVersion::Es2023, TO_REVERSED),
+  polyfill(Api::Method("toSpliced"), "es.array.to-spliced", EsVersion::Es2023, TO_REVERSED),
+  polyfill(Api::ArrayMethod("with"), "es.array.with", EsVersion::Es2023, TO_REVERSED),
+  polyfill(Api::Method("findLast"), "es.array.find-last", EsVersion::Es2023, FIND_LAST),
+  polyfill(
+    Api::Method("findLastIndex"),
+    "es.array.find-last-index",
+    EsVersion::Es2023,
+    FIND_LAST,
+  ),
+  polyfill(
+    Api::Global("structuredClone"),
+    "web.structured-clone",
+    EsVersion::Es2022,
+    [(98, 0), (98, 0), (94, 0), (15, 4), (84, 0), (15, 4), (18, 0), (17, 0)],
+  ),
+  polyfill(
+    Api::Static("Object", "hasOwn"),
+    "es.object.has-own",
+    EsVersion::Es2022,
+    [(93, 0), (93, 0), (92, 0), (15, 4), (79, 0), (15, 4), (17, 0), (16, 9)],
+  ),
+  polyfill(Api::ArrayMethod("at"), "es.array.at", EsVersion::Es2022, AT),
+  polyfill(Api::StringMethod("at"), "es.string.at-alternative", EsVersion::Es2022, AT),
+  polyfill(
+    Api::Static("Promise", "any"),
+    "es.promise.any",
+    EsVersion::Es2021,
+    [(85, 0), (85, 0), (79, 0), (14, 0), (71, 0), (14, 0), (14, 0), (15, 0)],
+  ),
+  polyfill(
+    Api::Method("replaceAll"),
+    "es.string.replace-all",
+    EsVersion::Es2021,
+    [(85, 0), (85, 0), (77, 0), (13, 1), (71, 0), (13, 4), (14, 0), (15, 0)],
+  ),
+  polyfill(
+    Api::Static("Promise", "allSettled"),
+    "es.promise.all-settled",
+    EsVersion::Es2020,
+    [(76, 0), (79, 0), (71, 0), (13, 0), (63, 0), (13, 0), (12, 0), (12, 9)],
+  ),
+  polyfill(
+    Api::Global("globalThis"),
+    "es.global-this",
+    EsVersion::Es2020,
+    [(71, 0), (79, 0), (65, 0), (12, 1), (58, 0), (12, 2), (10, 0), (12, 0)],
+  ),
+  polyfill(Api::Method("flat"), "es.array.flat", EsVersion::Es2019, FLAT),
+  polyfill(Api::Method("flatMap"), "es.array.flat-map", EsVersion::Es2019, FLAT),
+];
+
+/// Inject the side-effect imports of the `core-js` modules for the APIs that are used by the module
+/// and not supported by the target, e.g. `import "core-js/modules/es.array.find-last.js"`.
+/// The imports are resolved by the import analyzer like the other imports.
+pub fn polyfill_imports(target: EsVersion, browsers: Option<Browsers>, unresolved_mark: Mark) -> impl Pass {
+  visit_mut_pass(PolyfillImports {
+    target,
+    browsers,
+    unresolved_mark,
+  })
+}
+
+struct PolyfillImports {
+  target: EsVersion,
+  browsers: Option<Browsers>,
+  unresolved_mark: Mark,
+}
+
+impl PolyfillImports {
+  fn is_supported(&self, polyfill: &Polyfill) -> bool {
+    match &self.browsers {
+      Some(browsers) => browsers.supports(&polyfill.support),
+      None => polyfill.es_version <= self.target,
+    }
+  }
+}
+
+impl VisitMut for PolyfillImports {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module(&mut self, module: &mut Module) {
+    let mut usage = Usage {
+      unresolved_mark: self.unresolved_mark,
+      modules: IndexSet::new(),
+    };
+    module.visit_with(&mut usage);
+    let imports = POLYFILLS
+      .iter()
+      .filter(|polyfill| usage.modules.contains(polyfill.module) && !self.is_supported(polyfill))
+      .map(|polyfill| {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+          span: DUMMY_SP,
+          specifiers: vec![],
+          src: Box::new(new_str(&format!("core-js/modules/{}.js", polyfill.module))),
+          type_only: false,
+          with: None,
+          phase: Default::default(),
+        }))
+      })
+      .collect::<Vec<_>>();
+    // the polyfills are imported before the other modules
+    module.body.splice(0..0, imports);
+  }
+}
+
+/// Collect the polyfill modules of the APIs that are used.
+struct Usage {
+  unresolved_mark: Mark,
+  modules: IndexSet<&'static str>,
+}
+
+impl Usage {
+  fn is_global(&self, ident: &Ident) -> bool {
+    ident.ctxt.outer() == self.unresolved_mark
+  }
+
+  /// Check the method of the call, e.g. `arr.findLast()` or `arr?.findLast()`.
+  fn check_method_call(&mut self, callee: &Expr) {
+    let member = match callee {
+      Expr::Member(member) => member,
+      Expr::OptChain(OptChainExpr { base, .. }) => match base.as_ref() {
+        OptChainBase::Member(member) => member,
+        OptChainBase::Call(_) => return,
+      },
+      _ => return,
+    };
+    let MemberProp::Ident(prop) = &member.prop else {
+      return;
+    };
+    let is_array = member.obj.is_array();
+    let is_string = matches!(member.obj.as_ref(), Expr::Lit(Lit::Str(_)) | Expr::Tpl(_));
+    for polyfill in &POLYFILLS {
+      let matched = match polyfill.api {
+        Api::Method(method) => prop.sym == method,
+        Api::ArrayMethod(method) => is_array && prop.sym == method,
+        Api::StringMethod(method) => is_string && prop.sym == method,
+        Api::Global(_) | Api::Static(..) => false,
+      };
+      if matched {
+        self.modules.insert(polyfill.module);
+      }
+    }
+  }
+}
+
+impl Visit for Usage {
+  noop_visit_type!();
+
+  fn visit_ident(&mut self, ident: &Ident) {
+    if self.is_global(ident) {
+      for polyfill in &POLYFILLS {
+        if matches!(polyfill.api, Api::Global(name) if ident.sym == name) {
+          self.modules.insert(polyfill.module);
+        }
+      }
+    }
+  }
+
+  fn visit_member_expr(&mut self, expr: &MemberExpr) {
+    if let MemberProp::Ident(prop) = &expr.prop
+      && let Some(obj) = expr.obj.as_ident().filter(|obj| self.is_global(obj))
+    {
+      for polyfill in &POLYFILLS {
+        if matches!(polyfill.api, Api::Static(name, method) if obj.sym == name && prop.sym == method) {
+          self.modules.insert(polyfill.module);
+        }
+      }
+    }
+    expr.visit_children_with(self);
+  }
+
+  fn visit_call_expr(&mut self, call: &CallExpr) {
+    if let Callee::Expr(callee) = &call.callee {
+      self.check_method_call(callee);
+    }
+    call.visit_children_with(self);
+  }
+
+  fn visit_opt_call(&mut self, call: &OptCall) {
+    self.check_method_call(&call.callee);
+    call.visit_children_with(self);
+  }
+}
@@ -0,0 +1,968 @@
+// a port of the core of https://github.com/ryansolid/dom-expressions/tree/main/packages/babel-plugin-jsx-dom-expressions
+use crate::swc_helpers::*;
+use indexmap::{IndexMap, IndexSet};
+use serde::Deserialize;
+use swc_common::DUMMY_SP;
+use swc_common::util::take::Take;
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::{ExprFactory, private_ident, quote_ident};
+use swc_ecmascript::visit::{Visit, VisitMut, VisitMutWith, VisitWith, noop_visit_mut_type, noop_visit_type, visit_mut_pass};
+
+/// Options for the Solid JSX transform.
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SolidOptions {
+  /// `"dom"` or `"ssr"`, default is `"dom"`.
+  pub generate: Option<SolidGenerate>,
+  /// delegate the events to the document, default is enabled.
+  pub delegate_events: Option<bool>,
+}
+
+/// The output mode of the Solid JSX transform.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SolidGenerate {
+  /// The DOM operations for the browsers.
+  Dom,
+  /// The HTML strings for the server rendering.
+  Ssr,
+}
+
+/// Check if the JSX import source is `solid-js`, e.g. `solid-js`, `https://esm.sh/solid-js@1.9.0`.
+pub fn is_solid_import_source(import_source: &str) -> bool {
+  import_source == "solid-js"
+    || import_source.ends_with("/solid-js")
+    || import_source.split('/').any(|segment| segment.starts_with("solid-js@"))
+}
+
+/// Compile the JSX to the `template()`/`insert()`/`effect()` calls of `solid-js/web`,
+/// `module_name` is the module that the runtime functions are imported from.
+pub fn solid_jsx(module_name: String, options: SolidOptions) -> impl Pass {
+  visit_mut_pass(SolidJsx {
+    ssr: options.generate == Some(SolidGenerate::Ssr),
+    delegate_events: options.delegate_events.unwrap_or(true),
+    module_name,
+    runtime: IndexMap::new(),
+    templates: Vec::new(),
+    events: IndexSet::new(),
+  })
+}
+
+const VOID_ELEMENTS: [&str; 14] = [
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+const DELEGATED_EVENTS: [&str; 22] = [
+  "beforeinput",
+  "click",
+  "dblclick",
+  "contextmenu",
+  "focusin",
+  "focusout",
+  "input",
+  "keydown",
+  "keyup",
+  "mousedown",
+  "mousemove",
+  "mouseout",
+  "mouseover",
+  "mouseup",
+  "pointerdown",
+  "pointermove",
+  "pointerout",
+  "pointerover",
+  "pointerup",
+  "touchend",
+  "touchmove",
+  "touchstart",
+];
+
+/// The attributes that are set as the element properties instead of `setAttribute`.
+const PROPERTIES: [&str; 7] = ["value", "checked", "selected", "muted", "textContent", "innerHTML", "innerText"];
+
+struct SolidJsx {
+  ssr: bool,
+  delegate_events: bool,
+  module_name: String,
+  /// the runtime functions used by the module, e.g. `template` -> `_$template`
+  runtime: IndexMap<&'static str, Ident>,
+  /// the hoisted templates
+  templates: Vec<VarDeclarator>,
+  /// the delegated events
+  events: IndexSet<String>,
+}
+
+/// A normalized JSX child.
+enum Child {
+  /// the decoded text, the adjacent texts are merged.
+  Text(String),
+  /// a native element, e.g. `<div>`
+  Element(JSXElement),
+  /// an expression, a component or a fragment.
+  Expr(Box<Expr>),
+}
+
+/// The template of a native element for the DOM output.
+#[derive(Default)]
+struct DomTemplate {
+  html: String,
+  /// the references of the child nodes, e.g. `_el$2 = _el$.firstChild`
+  decls: Vec<VarDeclarator>,
+  /// the dynamic parts, e.g. `insert(_el$, count)`
+  stmts: Vec<Stmt>,
+}
+
+/// The template of a native element for the SSR output, the `parts` are joined by the `values`.
+struct SsrTemplate {
+  parts: Vec<String>,
+  values: Vec<Expr>,
+}
+
+impl SsrTemplate {
+  fn push_str(&mut self, s: &str) {
+    self.parts.last_mut().unwrap().push_str(s);
+  }
+
+  fn push_value(&mut self, value: Expr) {
+    self.values.push(value);
+    self.parts.push(String::new());
+  }
+}
+
+impl SolidJsx {
+  fn runtime(&mut self, name: &'static str) -> Expr {
+    self
+      .runtime
+      .entry(name)
+      .or_insert_with(|| private_ident!(format!("_${}", name)))
+      .clone()
+      .into()
+  }
+
+  fn call_runtime(&mut self, name: &'static str, args: Vec<Expr>) -> Expr {
+    self
+      .runtime(name)
+      .as_call(DUMMY_SP, args.into_iter().map(|arg| arg.as_arg()).collect())
+  }
+
+  fn new_template(&mut self, init: Expr) -> Ident {
+    let id = private_ident!("_tmpl$");
+    self.templates.push(VarDeclarator {
+      span: DUMMY_SP,
+      name: id.clone().into(),
+      init: Some(Box::new(init)),
+      definite: false,
+    });
+    id
+  }
+
+  /// Transform the JSX inside of the expression.
+  fn transform_expr(&mut self, mut expr: Box<Expr>) -> Box<Expr> {
+    expr.visit_mut_with(self);
+    expr
+  }
+
+  fn transform_element(&mut self, el: JSXElement) -> Expr {
+    if is_component(&el.opening.name) {
+      return self.transform_component(el);
+    }
+    if self.ssr {
+      let mut t = SsrTemplate {
+        parts: vec![String::new()],
+        values: vec![],
+      };
+      self.build_ssr_element(el, &mut t);
+      if t.parts.len() == 2 && t.parts.iter().all(|part| part.is_empty()) {
+        // an element with spread attributes
+        return t.values.pop().unwrap();
+      }
+      let tmpl = if t.values.is_empty() {
+        self.new_template(str_expr(&t.parts.concat()))
+      } else {
+        self.new_template(Expr::Array(ArrayLit {
+          span: DUMMY_SP,
+          elems: t.parts.iter().map(|part| Some(str_expr(part).as_arg())).collect(),
+        }))
+      };
+      let mut args = vec![Expr::Ident(tmpl)];
+      args.extend(t.values);
+      return self.call_runtime("ssr", args);
+    }
+    let node = private_ident!("_el$");
+    let t = self.build_dom_element(el, &node);
+    let template = self.call_runtime("template", vec![str_expr(&t.html)]);
+    let tmpl = self.new_template(template);
+    let clone: Expr = Expr::Ident(tmpl).as_call(DUMMY_SP, vec![]);
+    if t.decls.is_empty() && t.stmts.is_empty() {
+      return clone;
+    }
+    // (() => { var _el$ = _tmpl$(), ...; ...; return _el$; })()
+    let mut decls = vec![VarDeclarator {
+      span: DUMMY_SP,
+      name: node.clone().into(),
+      init: Some(Box::new(clone)),
+      definite: false,
+    }];
+    decls.extend(t.decls);
+    let mut stmts = vec![Stmt::Decl(Decl::Var(Box::new(VarDecl {
+      kind: VarDeclKind::Var,
+      decls,
+      ..Default::default()
+    })))];
+    stmts.extend(t.stmts);
+    stmts.push(Stmt::Return(ReturnStmt {
+      span: DUMMY_SP,
+      arg: Some(Box::new(node.into())),
+    }));
+    Expr::Arrow(ArrowExpr {
+      body: Box::new(BlockStmtOrExpr::BlockStmt(BlockStmt {
+        stmts,
+        ..Default::default()
+      })),
+      ..Default::default()
+    })
+    .as_iife()
+    .into()
+  }
+
+  fn transform_fragment(&mut self, fragment: JSXFragment) -> Expr {
+    self.transform_children(fragment.children)
+  }
+
+  /// Transform the children of a fragment to an array, or a single expression.
+  fn transform_children(&mut self, children: Vec<JSXElementChild>) -> Expr {
+    let mut exprs = normalize_children(children)
+      .into_iter()
+      .map(|child| match child {
+        Child::Text(text) if self.ssr => str_expr(&escape_html(&text)),
+        Child::Text(text) => str_expr(&text),
+        Child::Element(el) => self.transform_element(el),
+        Child::Expr(expr) => {
+          let dynamic = is_dynamic(&expr);
+          let expr = *self.transform_expr(expr);
+          if self.ssr {
+            self.call_runtime("escape", vec![expr])
+          } else if dynamic {
+            self.call_runtime("memo", vec![expr.into_lazy_arrow(vec![]).into()])
+          } else {
+            expr
+          }
+        }
+      })
+      .collect::<Vec<_>>();
+    if exprs.len() == 1 {
+      return exprs.pop().unwrap();
+    }
+    Expr::Array(ArrayLit {
+      span: DUMMY_SP,
+      elems: exprs.into_iter().map(|expr| Some(expr.as_arg())).collect(),
+    })
+  }
+
+  /// `<Comp a={b} />` -> `createComponent(Comp, { get a() { return b } })`
+  fn transform_component(&mut self, el: JSXElement) -> Expr {
+    let component = jsx_name_to_expr(el.opening.name);
+    let props = self.build_props(el.opening.attrs, el.children);
+    self.call_runtime("createComponent", vec![component, props])
+  }
+
+  fn build_props(&mut self, attrs: Vec<JSXAttrOrSpread>, children: Vec<JSXElementChild>) -> Expr {
+    let mut segments = vec![];
+    let mut props = vec![];
+    for attr in attrs {
+      match attr {
+        JSXAttrOrSpread::SpreadElement(spread) => {
+          if !props.is_empty() {
+            segments.push(object_expr(std::mem::take(&mut props)));
+          }
+          segments.push(*self.transform_expr(spread.expr));
+        }
+        JSXAttrOrSpread::JSXAttr(attr) => {
+          let name = jsx_attr_name(&attr.name);
+          let value = match attr.value {
+            None => Expr::Lit(Lit::Bool(true.into())),
+            Some(JSXAttrValue::Str(s)) => Expr::Lit(Lit::Str(s)),
+            Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+              expr: JSXExpr::Expr(expr), ..
+            })) => {
+              if name == "ref" && is_assignable(&expr) {
+                // ref(r$) { var _ref$ = el; typeof _ref$ === "function" ? _ref$(r$) : el = r$ }
+                props.push(PropOrSpread::Prop(Box::new(Prop::Method(MethodProp {
+                  key: prop_name("ref"),
+                  function: Box::new(Function {
+                    params: vec![Param::from(Pat::from(quote_ident!("r$")))],
+                    body: Some(BlockStmt {
+                      stmts: vec![ref_stmt(
+                        &expr,
+                        |r| r.as_call(DUMMY_SP, vec![ident_expr("r$").as_arg()]),
+                        ident_expr("r$"),
+                      )],
+                      ..Default::default()
+                    }),
+                    ..Default::default()
+                  }),
+                }))));
+                continue;
+              }
+              let dynamic = is_dynamic(&expr);
+              let expr = *self.transform_expr(expr);
+              if dynamic {
+                props.push(getter_prop(&name, expr));
+                continue;
+              }
+              expr
+            }
+            Some(JSXAttrValue::JSXExprContainer(_)) => continue,
+            Some(JSXAttrValue::JSXElement(el)) => {
+              let expr = self.transform_element(*el);
+              props.push(getter_prop(&name, expr));
+              continue;
+            }
+            Some(JSXAttrValue::JSXFragment(fragment)) => {
+              let expr = self.transform_fragment(fragment);
+              props.push(getter_prop(&name, expr));
+              continue;
+            }
+          };
+          props.push(kv_prop(&name, value));
+        }
+      }
+    }
+
+    let mut children = normalize_children(children);
+    if children.len() == 1
+      && let Child::Text(text) = &children[0]
+    {
+      props.push(kv_prop("children", str_expr(text)));
+    } else if children.len() == 1
+      && let Child::Expr(expr) = &children[0]
+      && !is_dynamic(expr)
+      && !is_jsx(expr)
+    {
+      // e.g. `<For each={list}>{(item) => <li>{item}</li>}</For>`
+      let Child::Expr(expr) = children.pop().unwrap() else {
+        unreachable!()
+      };
+      let expr = *self.transform_expr(expr);
+      props.push(kv_prop("children", expr));
+    } else if !children.is_empty() {
+      let mut exprs = children
+        .into_iter()
+        .map(|child| match child {
+          Child::Text(text) => str_expr(&text),
+          Child::Element(el) => self.transform_element(el),
+          Child::Expr(expr) => *self.transform_expr(expr),
+        })
+        .collect::<Vec<_>>();
+      let value = if exprs.len() == 1 {
+        exprs.pop().unwrap()
+      } else {
+        Expr::Array(ArrayLit {
+          span: DUMMY_SP,
+          elems: exprs.into_iter().map(|expr| Some(expr.as_arg())).collect(),
+        })
+      };
+      props.push(getter_prop("children", value));
+    }
+
+    if segments.is_empty() {
+      return object_expr(props);
+    }
+    if !props.is_empty() {
+      segments.push(object_expr(props));
+    }
+    self.call_runtime("mergeProps", segments)
+  }
+
+  fn build_dom_element(&mut self, el: JSXElement, node: &Ident) -> DomTemplate {
+    let mut t = DomTemplate::default();
+    let tag = jsx_element_name(&el.opening.name);
+    let has_children = !el.children.is_empty();
+    t.html.push('<');
+    t.html.push_str(&tag);
+    if el
+      .opening
+      .attrs
+      .iter()
+      .any(|attr| matches!(attr, JSXAttrOrSpread::SpreadElement(_)))
+    {
+      // spread(_el$, mergeProps(props, { ... }), false, true)
+      let props = self.build_props(el.opening.attrs, vec![]);
+      let stmt = self.call_runtime("spread", vec![Expr::Ident(node.clone()), props, false.into(), has_children.into()]);
+      t.stmts.push(stmt.into_stmt());
+    } else {
+      for attr in el.opening.attrs {
+        if let JSXAttrOrSpread::JSXAttr(attr) = attr {
+          self.build_dom_attr(attr, node, &mut t);
+        }
+      }
+    }
+    t.html.push('>');
+    if VOID_ELEMENTS.contains(&tag.as_str()) {
+      return t;
+    }
+
+    enum Slot {
+      Text(String),
+      Element(Ident, DomTemplate),
+      Marker(Ident),
+    }
+    let children = normalize_children(el.children);
+    let count = children.len();
+    let mut slots: Vec<Slot> = vec![];
+    let mut inserts = vec![];
+    let mut children = children.into_iter().enumerate().peekable();
+    while let Some((i, child)) = children.next() {
+      match child {
+        Child::Text(text) => slots.push(Slot::Text(text)),
+        Child::Element(el) => {
+          let child_node = private_ident!("_el$");
+          let child_template = self.build_dom_element(el, &child_node);
+          slots.push(Slot::Element(child_node, child_template));
+        }
+        Child::Expr(expr) => {
+          let dynamic = is_dynamic(&expr);
+          let expr = *self.transform_expr(expr);
+          let value = if dynamic { expr.into_lazy_arrow(vec![]).into() } else { expr };
+          let has_next_node = children.peek().is_some_and(|(_, child)| !matches!(child, Child::Expr(_)));
+          let marker = if count == 1 {
+            None
+          } else if has_next_node || (i + 1 < count && !slots.is_empty()) {
+            // use a `<!>` comment as the marker of the insert position
+            let marker = private_ident!("_el$");
+            slots.push(Slot::Marker(marker.clone()));
+            Some(Expr::Ident(marker))
+          } else {
+            Some(Expr::Lit(Lit::Null(Null { span: DUMMY_SP })))
+          };
+          inserts.push((value, marker));
+        }
+      }
+    }
+
+    // declare the references of the child nodes until the last one that is used
+    let needs_ref = |slot: &Slot| match slot {
+      Slot::Text(_) => false,
+      Slot::Element(_, child) => !child.decls.is_empty() || !child.stmts.is_empty(),
+      Slot::Marker(_) => true,
+    };
+    let last_ref = slots.iter().rposition(needs_ref);
+    let mut prev: Option<Ident> = None;
+    let mut child_decls = vec![];
+    let mut child_stmts = vec![];
+    for (i, slot) in slots.into_iter().enumerate() {
+      let id = match slot {
+        Slot::Text(text) => {
+          t.html.push_str(&escape_html(&text));
+          private_ident!("_el$")
+        }
+        Slot::Element(id, child) => {
+          t.html.push_str(&child.html);
+          child_decls.extend(child.decls);
+          child_stmts.extend(child.stmts);
+          id
+        }
+        Slot::Marker(id) => {
+          t.html.push_str("<!>");
+          id
+        }
+      };
+      if last_ref.is_some_and(|last| i <= last) {
+        let init = match &prev {
+          Some(prev) => Expr::Ident(prev.clone()).make_member(quote_ident!("nextSibling")),
+          None => Expr::Ident(node.clone()).make_member(quote_ident!("firstChild")),
+        };
+        t.decls.push(VarDeclarator {
+          span: DUMMY_SP,
+          name: id.clone().into(),
+          init: Some(Box::new(init.into())),
+          definite: false,
+        });
+        prev = Some(id);
+      }
+    }
+    t.decls.extend(child_decls);
+    t.stmts.extend(child_stmts);
+    for (value, marker) in inserts {
+      let mut args = vec![Expr::Ident(node.clone()), value];
+      args.extend(marker);
+      let stmt = self.call_runtime("insert", args);
+      t.stmts.push(stmt.into_stmt());
+    }
+    t.html.push_str("</");
+    t.html.push_str(&tag);
+    t.html.push('>');
+    t
+  }
+
+  fn build_dom_attr(&mut self, attr: JSXAttr, node: &Ident, t: &mut DomTemplate) {
+    let name = jsx_attr_name(&attr.name);
+    let expr = match attr.value {
+      None => {
+        t.html.push_str(&format!(" {}", name));
+        return;
+      }
+      Some(JSXAttrValue::Str(s)) => {
+        push_html_attr(&mut t.html, &name, &s.value.to_string_lossy());
+        return;
+      }
+      Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+        expr: JSXExpr::Expr(expr), ..
+      })) => expr,
+      Some(JSXAttrValue::JSXExprContainer(_)) => return,
+      Some(JSXAttrValue::JSXElement(el)) => Box::new(Expr::JSXElement(el)),
+      Some(JSXAttrValue::JSXFragment(fragment)) => Box::new(Expr::JSXFragment(fragment)),
+    };
+    if !name.contains(':') && !name.starts_with("on") && name != "ref" {
+      match static_attr_value(&expr) {
+        Some(Some(value)) => {
+          push_html_attr(&mut t.html, &name, &value);
+          return;
+        }
+        Some(None) => {
+          if matches!(&*expr, Expr::Lit(Lit::Bool(Bool { value: true, .. }))) {
+            t.html.push_str(&format!(" {}", name));
+          }
+          return;
+        }
+        None => {}
+      }
+    }
+
+    let el = Expr::Ident(node.clone());
+    if name == "ref" {
+      let stmt = if is_assignable(&expr) {
+        // var _ref$ = el; typeof _ref$ === "function" ? use(_ref$, _el$) : el = _el$
+        let use_fn = self.runtime("use");
+        ref_stmt(
+          &expr,
+          |r| use_fn.as_call(DUMMY_SP, vec![r.as_arg(), el.clone().as_arg()]),
+          el.clone(),
+        )
+      } else {
+        let expr = *self.transform_expr(expr);
+        self.call_runtime("use", vec![expr, el]).into_stmt()
+      };
+      t.stmts.push(stmt);
+      return;
+    }
+    if let Some(directive) = name.strip_prefix("use:") {
+      // use(directive, _el$, () => value)
+      let expr = *self.transform_expr(expr);
+      let stmt = self.call_runtime("use", vec![ident_expr(directive), el, expr.into_lazy_arrow(vec![]).into()]);
+      t.stmts.push(stmt.into_stmt());
+      return;
+    }
+    let event = match name.strip_prefix("on:") {
+      Some(event) => Some((event.to_owned(), false)),
+      None if name.starts_with("on") && name.chars().nth(2).is_some_and(|c| c.is_ascii_uppercase()) => {
+        let event = name[2..].to_lowercase();
+        let delegated = self.delegate_events && DELEGATED_EVENTS.contains(&event.as_str());
+        Some((event, delegated))
+      }
+      None => None,
+    };
+    if let Some((event, delegated)) = event {
+      let handler = *self.transform_expr(expr);
+      let stmt = if delegated {
+        // _el$.$$click = handler
+        let prop = quote_ident!(format!("$${}", event));
+        self.events.insert(event);
+        handler.make_assign_to(AssignOp::Assign, SimpleAssignTarget::Member(el.make_member(prop)).into())
+      } else {
+        // _el$.addEventListener("change", handler)
+        el.make_member(quote_ident!("addEventListener"))
+          .as_call(DUMMY_SP, vec![str_expr(&event).as_arg(), handler.as_arg()])
+      };
+      t.stmts.push(stmt.into_stmt());
+      return;
+    }
+
+    let dynamic = is_dynamic(&expr);
+    let value = *self.transform_expr(expr);
+    let set = match name.as_str() {
+      "class" | "className" => self.call_runtime("className", vec![el, value]),
+      "style" => self.call_runtime("style", vec![el, value]),
+      "classList" => self.call_runtime("classList", vec![el, value]),
+      _ => {
+        let prop = name
+          .strip_prefix("prop:")
+          .or(PROPERTIES.contains(&name.as_str()).then_some(name.as_str()));
+        if let Some(prop) = prop {
+          value.make_assign_to(
+            AssignOp::Assign,
+            SimpleAssignTarget::Member(el.make_member(quote_ident!(prop))).into(),
+          )
+        } else {
+          let name = name.strip_prefix("attr:").unwrap_or(&name);
+          self.call_runtime("setAttribute", vec![el, str_expr(name), value])
+        }
+      }
+    };
+    let stmt = if dynamic {
+      self.call_runtime("effect", vec![set.into_lazy_arrow(vec![]).into()])
+    } else {
+      set
+    };
+    t.stmts.push(stmt.into_stmt());
+  }
+
+  fn build_ssr_element(&mut self, el: JSXElement, t: &mut SsrTemplate) {
+    let tag = jsx_element_name(&el.opening.name);
+    if el
+      .opening
+      .attrs
+      .iter()
+      .any(|attr| matches!(attr, JSXAttrOrSpread::SpreadElement(_)))
+    {
+      // ssrElement("div", mergeProps(props, { ... }), () => children, false)
+      let props = self.build_props(el.opening.attrs, vec![]);
+      let children = self.transform_children(el.children);
+      let value = self.call_runtime(
+        "ssrElement",
+        vec![str_expr(&tag), props, children.into_lazy_arrow(vec![]).into(), false.into()],
+      );
+      t.push_value(value);
+      return;
+    }
+    t.push_str(&format!("<{}", tag));
+    let mut inner_html = None;
+    for attr in el.opening.attrs {
+      let JSXAttrOrSpread::JSXAttr(attr) = attr else {
+        continue;
+      };
+      let name = jsx_attr_name(&attr.name);
+      if name == "ref" || name.starts_with("on") || name.starts_with("use:") || name.starts_with("prop:") {
+        continue;
+      }
+      let name = match name.strip_prefix("attr:").unwrap_or(&name) {
+        "className" => "class".to_owned(),
+        name => name.to_owned(),
+      };
+      let expr = match attr.value {
+        None => {
+          t.push_str(&format!(" {}", name));
+          continue;
+        }
+        Some(JSXAttrValue::Str(s)) => {
+          let mut html = String::new();
+          push_html_attr(&mut html, &name, &s.value.to_string_lossy());
+          t.push_str(&html);
+          continue;
+        }
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::Expr(expr), ..
+        })) => expr,
+        _ => continue,
+      };
+      match static_attr_value(&expr) {
+        Some(Some(value)) => {
+          let mut html = String::new();
+          push_html_attr(&mut html, &name, &value);
+          t.push_str(&html);
+          continue;
+        }
+        Some(None) => {
+          if matches!(&*expr, Expr::Lit(Lit::Bool(Bool { value: true, .. }))) {
+            t.push_str(&format!(" {}", name));
+          }
+          continue;
+        }
+        None => {}
+      }
+      let value = *self.transform_expr(expr);
+      match name.as_str() {
+        "innerHTML" => inner_html = Some(value),
+        "textContent" | "innerText" => inner_html = Some(self.call_runtime("escape", vec![value])),
+        "style" => {
+          t.push_str(" style=\"");
+          let value = self.call_runtime("ssrStyle", vec![value]);
+          t.push_value(value);
+          t.push_str("\"");
+        }
+        "classList" => {
+          t.push_str(" class=\"");
+          let value = self.call_runtime("ssrClassList", vec![value]);
+          t.push_value(value);
+          t.push_str("\"");
+        }
+        _ => {
+          // ssrAttribute("title", escape(title(), true), false)
+          let escaped = self.call_runtime("escape", vec![value, true.into()]);
+          let value = self.call_runtime("ssrAttribute", vec![str_expr(&name), escaped, false.into()]);
+          t.push_str(" ");
+          t.push_value(value);
+        }
+      }
+    }
+    t.push_str(">");
+    if VOID_ELEMENTS.contains(&tag.as_str()) {
+      return;
+    }
+    if let Some(inner_html) = inner_html {
+      t.push_value(inner_html);
+    } else {
+      for child in normalize_children(el.children) {
+        match child {
+          Child::Text(text) => t.push_str(&escape_html(&text)),
+          Child::Element(el) => self.build_ssr_element(el, t),
+          Child::Expr(expr) => {
+            let is_jsx = is_jsx(&expr);
+            let expr = *self.transform_expr(expr);
+            let value = if is_jsx { expr } else { self.call_runtime("escape", vec![expr]) };
+            t.push_value(value);
+          }
+        }
+      }
+    }
+    t.push_str(&format!("</{}>", tag));
+  }
+}
+
+impl VisitMut for SolidJsx {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module(&mut self, module: &mut Module) {
+    module.visit_mut_children_with(self);
+    if !self.events.is_empty() {
+      // delegateEvents(["click"])
+      let events = Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: self.events.iter().map(|event| Some(str_expr(event).as_arg())).collect(),
+      });
+      let stmt = self.call_runtime("delegateEvents", vec![events]);
+      module.body.push(ModuleItem::Stmt(stmt.into_stmt()));
+    }
+    if self.runtime.is_empty() {
+      return;
+    }
+    let mut items = vec![ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+      span: DUMMY_SP,
+      specifiers: self
+        .runtime
+        .iter()
+        .map(|(name, local)| {
+          ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: local.clone(),
+            imported: Some(ModuleExportName::Ident(quote_ident!(*name).into())),
+            is_type_only: false,
+          })
+        })
+        .collect(),
+      src: Box::new(self.module_name.as_str().into()),
+      type_only: false,
+      with: None,
+      phase: Default::default(),
+    }))];
+    if !self.templates.is_empty() {
+      items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        kind: VarDeclKind::Var,
+        decls: std::mem::take(&mut self.templates),
+        ..Default::default()
+      })))));
+    }
+    // the templates are declared after the imports
+    let pos = module
+      .body
+      .iter()
+      .position(|item| !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+      .unwrap_or(module.body.len());
+    module.body.splice(pos..pos, items);
+  }
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    match expr {
+      Expr::JSXElement(_) => {
+        let Expr::JSXElement(el) = expr.take() else { unreachable!() };
+        *expr = self.transform_element(*el);
+      }
+      Expr::JSXFragment(_) => {
+        let Expr::JSXFragment(fragment) = expr.take() else { unreachable!() };
+        *expr = self.transform_fragment(fragment);
+      }
+      _ => expr.visit_mut_children_with(self),
+    }
+  }
+}
+
+fn normalize_children(children: Vec<JSXElementChild>) -> Vec<Child> {
+  let mut normalized: Vec<Child> = vec![];
+  let push_text = |normalized: &mut Vec<Child>, text: String| {
+    if let Some(Child::Text(prev)) = normalized.last_mut() {
+      prev.push_str(&text);
+    } else {
+      normalized.push(Child::Text(text));
+    }
+  };
+  for child in children {
+    match child {
+      JSXElementChild::JSXText(text) => {
+        if let Some(text) = jsx_text(&text.value) {
+          push_text(&mut normalized, text);
+        }
+      }
+      JSXElementChild::JSXExprContainer(JSXExprContainer {
+        expr: JSXExpr::Expr(expr), ..
+      }) => match *expr {
+        Expr::Lit(Lit::Str(s)) => push_text(&mut normalized, s.value.to_string_lossy().into_owned()),
+        Expr::Lit(Lit::Num(n)) => push_text(&mut normalized, n.value.to_string()),
+        expr => normalized.push(Child::Expr(Box::new(expr))),
+      },
+      JSXElementChild::JSXExprContainer(_) => {}
+      JSXElementChild::JSXSpreadChild(spread) => normalized.push(Child::Expr(spread.expr)),
+      JSXElementChild::JSXElement(el) => {
+        if is_component(&el.opening.name) {
+          normalized.push(Child::Expr(Box::new(Expr::JSXElement(el))));
+        } else {
+          normalized.push(Child::Element(*el));
+        }
+      }
+      JSXElementChild::JSXFragment(fragment) => normalized.push(Child::Expr(Box::new(Expr::JSXFragment(fragment)))),
+    }
+  }
+  normalized
+}
+
+fn escape_html(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn push_html_attr(html: &mut String, name: &str, value: &str) {
+  let name = match name {
+    "className" => "class",
+    "htmlFor" => "for",
+    name => name.strip_prefix("attr:").unwrap_or(name),
+  };
+  html.push_str(&format!(" {}=\"{}\"", name, value.replace('&', "&amp;").replace('"', "&quot;")));
+}
+
+/// Get the static value of an attribute, `Some(None)` for `true`, `false`, `null` and `undefined`.
+fn static_attr_value(expr: &Expr) -> Option<Option<String>> {
+  match expr {
+    Expr::Lit(Lit::Str(s)) => Some(Some(s.value.to_string_lossy().into_owned())),
+    Expr::Lit(Lit::Num(n)) => Some(Some(n.value.to_string())),
+    Expr::Lit(Lit::Bool(_)) | Expr::Lit(Lit::Null(_)) => Some(None),
+    Expr::Ident(id) if id.sym == "undefined" => Some(None),
+    Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+      .quasis
+      .first()
+      .and_then(|q| q.cooked.as_ref())
+      .map(|s| Some(s.to_string_lossy().into_owned())),
+    _ => None,
+  }
+}
+
+fn is_component(name: &JSXElementName) -> bool {
+  match name {
+    JSXElementName::Ident(id) => id.sym.starts_with(|c: char| c.is_ascii_uppercase()) || id.sym.contains('.'),
+    JSXElementName::JSXMemberExpr(_) => true,
+    JSXElementName::JSXNamespacedName(_) => false,
+  }
+}
+
+fn is_jsx(expr: &Expr) -> bool {
+  matches!(expr, Expr::JSXElement(_) | Expr::JSXFragment(_))
+}
+
+fn is_assignable(expr: &Expr) -> bool {
+  matches!(expr, Expr::Ident(_) | Expr::Member(_))
+}
+
+/// Check if the expression may read a signal, which means it has calls or member accesses outside of functions.
+fn is_dynamic(expr: &Expr) -> bool {
+  struct DynamicFinder {
+    found: bool,
+  }
+  impl Visit for DynamicFinder {
+    noop_visit_type!();
+    fn visit_call_expr(&mut self, _: &CallExpr) {
+      self.found = true;
+    }
+    fn visit_member_expr(&mut self, _: &MemberExpr) {
+      self.found = true;
+    }
+    fn visit_opt_chain_expr(&mut self, _: &OptChainExpr) {
+      self.found = true;
+    }
+    fn visit_tagged_tpl(&mut self, _: &TaggedTpl) {
+      self.found = true;
+    }
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+    fn visit_function(&mut self, _: &Function) {}
+    fn visit_jsx_element(&mut self, _: &JSXElement) {}
+    fn visit_jsx_fragment(&mut self, _: &JSXFragment) {}
+  }
+  let mut finder = DynamicFinder { found: false };
+  expr.visit_with(&mut finder);
+  finder.found
+}
+
+/// `var _ref$ = r; typeof _ref$ === "function" ? <call _ref$> : r = el;`
+fn ref_stmt(target: &Expr, call: impl FnOnce(Expr) -> Expr, el: Expr) -> Stmt {
+  let ref_id = private_ident!("_ref$");
+  let assign_target = match target {
+    Expr::Ident(id) => SimpleAssignTarget::Ident(id.clone().into()),
+    Expr::Member(member) => SimpleAssignTarget::Member(member.clone()),
+    _ => unreachable!(),
+  };
+  let test = Expr::Unary(UnaryExpr {
+    span: DUMMY_SP,
+    op: UnaryOp::TypeOf,
+    arg: Box::new(Expr::Ident(ref_id.clone())),
+  })
+  .make_eq(str_expr("function"));
+  let cond = Expr::Cond(CondExpr {
+    span: DUMMY_SP,
+    test: Box::new(test),
+    cons: Box::new(call(Expr::Ident(ref_id.clone()))),
+    alt: Box::new(el.make_assign_to(AssignOp::Assign, assign_target.into())),
+  });
+  Stmt::Block(BlockStmt {
+    stmts: vec![
+      Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        kind: VarDeclKind::Var,
+        decls: vec![VarDeclarator {
+          span: DUMMY_SP,
+          name: ref_id.into(),
+          init: Some(Box::new(target.clone())),
+          definite: false,
+        }],
+        ..Default::default()
+      }))),
+      cond.into_stmt(),
+    ],
+    ..Default::default()
+  })
+}
+
+fn jsx_element_name(name: &JSXElementName) -> String {
+  match name {
+    JSXElementName::Ident(id) => id.sym.to_string(),
+    JSXElementName::JSXNamespacedName(ns) => format!("{}:{}", ns.ns.sym, ns.name.sym),
+    JSXElementName::JSXMemberExpr(member) => format!("{}.{}", jsx_object_name(&member.obj), member.prop.sym),
+  }
+}
+
+fn jsx_object_name(obj: &JSXObject) -> String {
+  match obj {
+    JSXObject::Ident(id) => id.sym.to_string(),
+    JSXObject::JSXMemberExpr(member) => format!("{}.{}", jsx_object_name(&member.obj), member.prop.sym),
+  }
+}
+
+/// `get key() { return value }`
+fn getter_prop(key: &str, value: Expr) -> PropOrSpread {
+  PropOrSpread::Prop(Box::new(Prop::Getter(GetterProp {
+    span: DUMMY_SP,
+    key: prop_name(key),
+    type_ann: None,
+    body: Some(BlockStmt {
+      stmts: vec![Stmt::Return(ReturnStmt {
+        span: DUMMY_SP,
+        arg: Some(Box::new(value)),
+      })],
+      ..Default::default()
+    }),
+  })))
+}
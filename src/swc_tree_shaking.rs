@@ -0,0 +1,257 @@
+use crate::resolver::{DependencyKind, Resolver};
+use crate::swc_helpers::is_call_expr_by_name;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use swc_atoms::Atom;
+use swc_common::Mark;
+use swc_common::pass::Repeat;
+use swc_ecma_transforms::optimization::simplify::{dce, dead_branch_remover, expr_simplifier};
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::find_pat_ids;
+use swc_ecmascript::visit::{Visit, VisitWith, noop_visit_type};
+
+/// The `usedExports` option, the export names that are imported by the other modules,
+/// or `"none"` if the module is only imported for the side effects.
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+pub enum UsedExports {
+  Names(Vec<String>),
+  Keyword(String),
+}
+
+/// Remove the dead branches, e.g. `if ("production" !== "production")` after the defines are replaced,
+/// and strip the unused code of the module, the passes are repeated until nothing is changed.
+pub fn tree_shaking(unresolved_mark: Mark) -> impl Pass {
+  Repeat::new((
+    expr_simplifier(unresolved_mark, Default::default()),
+    dead_branch_remover(unresolved_mark),
+    dce::dce(Default::default(), unresolved_mark),
+  ))
+}
+
+/// Drop the exports that are not in the `used_exports` and shake the code they leave,
+/// the imports that become unused are removed and the deps of the removed imports are dropped.
+pub fn prune_exports(used_exports: Vec<String>, unresolved_mark: Mark, resolver: Rc<RefCell<Resolver>>) -> impl Pass {
+  ExportPruner {
+    used_exports: used_exports.into_iter().map(Atom::from).collect(),
+    unresolved_mark,
+    resolver,
+  }
+}
+
+struct ExportPruner {
+  used_exports: HashSet<Atom>,
+  unresolved_mark: Mark,
+  resolver: Rc<RefCell<Resolver>>,
+}
+
+impl ExportPruner {
+  fn is_used(&self, name: &Atom) -> bool {
+    self.used_exports.contains(name)
+  }
+
+  /// Strip the `export` of the unused exports, the declarations are kept for the tree shaking.
+  fn prune_item(&self, item: ModuleItem, items: &mut Vec<ModuleItem>) {
+    let ModuleItem::ModuleDecl(decl) = item else {
+      items.push(item);
+      return;
+    };
+    match decl {
+      ModuleDecl::ExportDecl(ExportDecl { span, decl }) => match decl {
+        Decl::Var(var) => {
+          // split the declarators into the exported and the local ones, the order is kept
+          let VarDecl {
+            span: var_span,
+            ctxt,
+            kind,
+            declare,
+            decls,
+          } = *var;
+          let mut runs: Vec<(bool, Vec<VarDeclarator>)> = vec![];
+          for declarator in decls {
+            let used = find_pat_ids::<_, Ident>(&declarator.name).iter().any(|id| self.is_used(&id.sym));
+            match runs.last_mut() {
+              Some((run_used, run)) if *run_used == used => run.push(declarator),
+              _ => runs.push((used, vec![declarator])),
+            }
+          }
+          for (used, decls) in runs {
+            let decl = Decl::Var(Box::new(VarDecl {
+              span: var_span,
+              ctxt,
+              kind,
+              declare,
+              decls,
+            }));
+            if used {
+              items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { span, decl })));
+            } else {
+              items.push(ModuleItem::Stmt(Stmt::Decl(decl)));
+            }
+          }
+        }
+        Decl::Fn(FnDecl { ref ident, .. }) | Decl::Class(ClassDecl { ref ident, .. }) if !self.is_used(&ident.sym) => {
+          items.push(ModuleItem::Stmt(Stmt::Decl(decl)));
+        }
+        decl => items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { span, decl }))),
+      },
+      ModuleDecl::ExportDefaultDecl(ExportDefaultDecl { span, decl }) if !self.is_used(&"default".into()) => match decl {
+        DefaultDecl::Fn(FnExpr {
+          ident: Some(ident),
+          function,
+        }) => items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Fn(FnDecl {
+          ident,
+          declare: false,
+          function,
+        })))),
+        DefaultDecl::Class(ClassExpr { ident: Some(ident), class }) => items.push(ModuleItem::Stmt(Stmt::Decl(Decl::Class(ClassDecl {
+          ident,
+          declare: false,
+          class,
+        })))),
+        // the static blocks of an anonymous class may have side effects
+        DefaultDecl::Class(class) => items.push(ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+          span,
+          expr: Box::new(Expr::Class(class)),
+        }))),
+        _ => {}
+      },
+      ModuleDecl::ExportDefaultExpr(ExportDefaultExpr { span, expr }) if !self.is_used(&"default".into()) => {
+        items.push(ModuleItem::Stmt(Stmt::Expr(ExprStmt { span, expr })));
+      }
+      ModuleDecl::ExportNamed(mut export) => {
+        export
+          .specifiers
+          .retain(|specifier| self.is_used(&export_specifier_name(specifier)));
+        if !export.specifiers.is_empty() {
+          items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)));
+        }
+      }
+      decl => items.push(ModuleItem::ModuleDecl(decl)),
+    }
+  }
+}
+
+impl Pass for ExportPruner {
+  fn process(&mut self, program: &mut Program) {
+    let Program::Module(module) = program else {
+      return;
+    };
+
+    // the names that are exported by the module itself, the `export *` is dropped if it can't provide any used name
+    let mut local_exports = LocalExports::default();
+    module.visit_with(&mut local_exports);
+    let export_all_used = self.used_exports.iter().any(|name| !local_exports.0.contains(name));
+
+    // the side-effect imports are kept, e.g. `import "./style.css"`
+    let mut side_effect_imports = HashSet::new();
+    let mut items = Vec::with_capacity(module.body.len());
+    for item in std::mem::take(&mut module.body) {
+      match &item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) if import_decl.specifiers.is_empty() => {
+          side_effect_imports.insert(import_decl.src.value.clone());
+          items.push(item);
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportAll(_)) if !export_all_used => {}
+        _ => self.prune_item(item, &mut items),
+      }
+    }
+    module.body = items;
+
+    tree_shaking(self.unresolved_mark).process(program);
+
+    let Program::Module(module) = program else {
+      return;
+    };
+    // the imports whose specifiers are all removed by the tree shaking
+    module.body.retain(|item| match item {
+      ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
+        !import_decl.specifiers.is_empty() || side_effect_imports.contains(&import_decl.src.value)
+      }
+      _ => true,
+    });
+
+    // report the dependencies that survive only
+    let mut sources = Sources::default();
+    module.visit_with(&mut sources);
+    self
+      .resolver
+      .borrow_mut()
+      .deps
+      .retain(|dep| sources.0.contains(&(dep.kind, dep.resolved_url.clone())));
+  }
+}
+
+/// The exported name of the export specifier, e.g. `b` of `export { a as b }`.
+fn export_specifier_name(specifier: &ExportSpecifier) -> Atom {
+  match specifier {
+    ExportSpecifier::Named(ExportNamedSpecifier { orig, exported, .. }) => module_export_name(exported.as_ref().unwrap_or(orig)),
+    ExportSpecifier::Namespace(ExportNamespaceSpecifier { name, .. }) => module_export_name(name),
+    ExportSpecifier::Default(ExportDefaultSpecifier { exported }) => exported.sym.clone(),
+  }
+}
+
+fn module_export_name(name: &ModuleExportName) -> Atom {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.clone(),
+    ModuleExportName::Str(s) => s.value.to_string_lossy().into(),
+  }
+}
+
+/// Collect the names that are exported by the module itself, the `export *` is not included.
+#[derive(Default)]
+struct LocalExports(HashSet<Atom>);
+
+impl Visit for LocalExports {
+  noop_visit_type!();
+
+  fn visit_module_decl(&mut self, decl: &ModuleDecl) {
+    match decl {
+      ModuleDecl::ExportDecl(ExportDecl { decl, .. }) => match decl {
+        Decl::Var(var) => self.0.extend(find_pat_ids::<_, Ident>(&var.decls).into_iter().map(|id| id.sym)),
+        Decl::Fn(FnDecl { ident, .. }) | Decl::Class(ClassDecl { ident, .. }) => {
+          self.0.insert(ident.sym.clone());
+        }
+        _ => {}
+      },
+      ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+        self.0.insert("default".into());
+      }
+      ModuleDecl::ExportNamed(export) => self.0.extend(export.specifiers.iter().map(export_specifier_name)),
+      _ => {}
+    }
+  }
+}
+
+/// Collect the sources of the import/export statements and the dynamic imports that survive.
+#[derive(Default)]
+struct Sources(HashSet<(DependencyKind, String)>);
+
+impl Visit for Sources {
+  noop_visit_type!();
+
+  fn visit_module_decl(&mut self, decl: &ModuleDecl) {
+    let source = match decl {
+      ModuleDecl::Import(ImportDecl { src, .. }) => Some((DependencyKind::Static, src)),
+      ModuleDecl::ExportNamed(NamedExport { src: Some(src), .. }) => Some((DependencyKind::ReExport, src)),
+      ModuleDecl::ExportAll(ExportAll { src, .. }) => Some((DependencyKind::ExportAll, src)),
+      _ => None,
+    };
+    if let Some((kind, src)) = source {
+      self.0.insert((kind, src.value.to_string_lossy().into_owned()));
+    }
+    decl.visit_children_with(self);
+  }
+
+  fn visit_call_expr(&mut self, call: &CallExpr) {
+    if is_call_expr_by_name(call, "import")
+      && let Some(ExprOrSpread { expr, .. }) = call.args.first()
+      && let Expr::Lit(Lit::Str(src)) = expr.as_ref()
+    {
+      self.0.insert((DependencyKind::Dynamic, src.value.to_string_lossy().into_owned()));
+    }
+    call.visit_children_with(self);
+  }
+}
@@ -0,0 +1,598 @@
+// a port of the core of https://github.com/vuejs/babel-plugin-jsx
+use crate::swc_helpers::*;
+use crate::swc_prefresh::hash_string;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use swc_common::DUMMY_SP;
+use swc_common::util::take::Take;
+use swc_core::quote;
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::{ExprFactory, private_ident, quote_ident};
+use swc_ecmascript::visit::{VisitMut, VisitMutWith, noop_visit_mut_type, visit_mut_pass};
+
+/// Check if the JSX import source is `vue`, e.g. `vue`, `https://esm.sh/vue@3.5.0`.
+pub fn is_vue_import_source(import_source: &str) -> bool {
+  import_source == "vue" || import_source.ends_with("/vue") || import_source.split('/').any(|segment| segment.starts_with("vue@"))
+}
+
+/// Compile the JSX to the `createVNode()` calls of Vue, `module_name` is the module that the runtime functions are imported from.
+pub fn vue_jsx(module_name: String) -> impl Pass {
+  visit_mut_pass(VueJsx {
+    module_name,
+    runtime: IndexMap::new(),
+    is_slot: None,
+  })
+}
+
+/// Register the components that are created by `defineComponent` to the Vue HMR runtime.
+pub fn vue_hmr(specifier: &str) -> impl Pass + use<> {
+  visit_mut_pass(VueHmr {
+    specifier: specifier.to_owned(),
+  })
+}
+
+struct VueJsx {
+  module_name: String,
+  /// the runtime functions used by the module, e.g. `createVNode` -> `_createVNode`
+  runtime: IndexMap<&'static str, Ident>,
+  /// the `_isSlot` function to check if a child of the component is a slots object
+  is_slot: Option<Ident>,
+}
+
+impl VueJsx {
+  fn runtime(&mut self, name: &'static str) -> Expr {
+    self
+      .runtime
+      .entry(name)
+      .or_insert_with(|| private_ident!(format!("_{}", name)))
+      .clone()
+      .into()
+  }
+
+  fn call_runtime(&mut self, name: &'static str, args: Vec<Expr>) -> Expr {
+    self
+      .runtime(name)
+      .as_call(DUMMY_SP, args.into_iter().map(|arg| arg.as_arg()).collect())
+  }
+
+  /// Transform the JSX inside of the expression.
+  fn transform_expr(&mut self, mut expr: Box<Expr>) -> Expr {
+    expr.visit_mut_with(self);
+    *expr
+  }
+
+  fn transform_element(&mut self, el: JSXElement) -> Expr {
+    let tag_name = match &el.opening.name {
+      JSXElementName::Ident(id) if id.sym.starts_with(|c: char| c.is_ascii_lowercase()) => Some(id.sym.to_string()),
+      _ => None,
+    };
+    let (tag, native_tag) = match tag_name {
+      // the custom components registered by `app.component()`
+      Some(name) if name.contains('-') => (self.call_runtime("resolveComponent", vec![str_expr(&name)]), None),
+      Some(name) => (str_expr(&name), Some(name)),
+      None => (jsx_name_to_expr(el.opening.name), None),
+    };
+    let input_type = el.opening.attrs.iter().find_map(|attr| match attr {
+      JSXAttrOrSpread::JSXAttr(attr) if jsx_attr_name(&attr.name) == "type" => match &attr.value {
+        Some(JSXAttrValue::Str(s)) => Some(Some(s.value.to_string_lossy().into_owned())),
+        _ => Some(None),
+      },
+      _ => None,
+    });
+
+    let mut segments = vec![];
+    let mut props = vec![];
+    let mut keys = HashSet::new();
+    let mut directives = vec![];
+    let mut v_slots = None;
+    for attr in el.opening.attrs {
+      let attr = match attr {
+        JSXAttrOrSpread::SpreadElement(spread) => {
+          if !props.is_empty() {
+            segments.push(object_expr(std::mem::take(&mut props)));
+            keys.clear();
+          }
+          segments.push(self.transform_expr(spread.expr));
+          continue;
+        }
+        JSXAttrOrSpread::JSXAttr(attr) => attr,
+      };
+      let name = jsx_attr_name(&attr.name);
+      let value = match attr.value {
+        None => Expr::Lit(Lit::Bool(true.into())),
+        Some(JSXAttrValue::Str(s)) => Expr::Lit(Lit::Str(s)),
+        Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::Expr(expr), ..
+        })) => self.transform_expr(expr),
+        Some(JSXAttrValue::JSXExprContainer(_)) => continue,
+        Some(JSXAttrValue::JSXElement(el)) => self.transform_element(*el),
+        Some(JSXAttrValue::JSXFragment(fragment)) => self.transform_fragment(fragment),
+      };
+      match name.as_str() {
+        "v-show" => directives.push(array_expr(vec![self.runtime("vShow"), value])),
+        "v-slots" => v_slots = Some(value),
+        "v-html" => props.push(kv_prop("innerHTML", value)),
+        "v-text" => props.push(kv_prop("textContent", value)),
+        _ if name == "v-model" || name.starts_with("v-model:") => {
+          let (model, arg, modifiers) = parse_directive_value(name.strip_prefix("v-model:"), value);
+          let Some(target) = assign_target(&model) else {
+            continue;
+          };
+          // $event => model = $event
+          let event = quote_ident!("$event");
+          let update = Expr::Ident(event.clone().into())
+            .make_assign_to(AssignOp::Assign, target.into())
+            .into_lazy_arrow(vec![Pat::from(BindingIdent::from(Ident::from(event)))]);
+          let modifiers = (!modifiers.is_empty()).then(|| {
+            object_expr(
+              modifiers
+                .iter()
+                .map(|modifier| kv_prop(modifier, Expr::Lit(Lit::Bool(true.into()))))
+                .collect(),
+            )
+          });
+          let Some(tag) = &native_tag else {
+            // <Comp modelValue={model} onUpdate:modelValue={$event => model = $event} modelModifiers={{ trim: true }} />
+            let prop = arg.clone().unwrap_or_else(|| "modelValue".to_owned());
+            props.push(kv_prop(&prop, model));
+            props.push(kv_prop(&format!("onUpdate:{}", prop), update.into()));
+            if let Some(modifiers) = modifiers {
+              let key = match arg {
+                Some(arg) => format!("{}Modifiers", arg),
+                None => "modelModifiers".to_owned(),
+              };
+              props.push(kv_prop(&key, modifiers));
+            }
+            continue;
+          };
+          // withDirectives(createVNode("input", { "onUpdate:modelValue": $event => model = $event }), [[vModelText, model]])
+          props.push(kv_prop("onUpdate:modelValue", update.into()));
+          let directive = match (tag.as_str(), input_type.as_ref()) {
+            ("select", _) => "vModelSelect",
+            ("input", Some(Some(t))) if t == "checkbox" => "vModelCheckbox",
+            ("input", Some(Some(t))) if t == "radio" => "vModelRadio",
+            ("input", Some(None)) => "vModelDynamic",
+            _ => "vModelText",
+          };
+          let mut elems = vec![self.runtime(directive), model];
+          if let Some(modifiers) = modifiers {
+            elems.push(arg.map(|arg| str_expr(&arg)).unwrap_or_else(|| *Expr::undefined(DUMMY_SP)));
+            elems.push(modifiers);
+          }
+          directives.push(array_expr(elems));
+        }
+        _ if name.starts_with("v-") => {
+          // withDirectives(vnode, [[resolveDirective("focus"), value, arg, modifiers]])
+          let (directive, arg) = match name[2..].split_once(':') {
+            Some((directive, arg)) => (directive.to_owned(), Some(arg)),
+            None => (name[2..].to_owned(), None),
+          };
+          let (value, arg, modifiers) = parse_directive_value(arg, value);
+          let mut elems = vec![self.call_runtime("resolveDirective", vec![str_expr(&directive)]), value];
+          if arg.is_some() || !modifiers.is_empty() {
+            elems.push(arg.map(|arg| str_expr(&arg)).unwrap_or_else(|| *Expr::undefined(DUMMY_SP)));
+          }
+          if !modifiers.is_empty() {
+            elems.push(object_expr(
+              modifiers
+                .iter()
+                .map(|modifier| kv_prop(modifier, Expr::Lit(Lit::Bool(true.into()))))
+                .collect(),
+            ));
+          }
+          directives.push(array_expr(elems));
+        }
+        _ => {
+          // the `class`, `style` and `onXxx` props are merged by `mergeProps` instead of being overwritten
+          let mergeable = name == "class" || name == "style" || is_event_prop(&name);
+          if mergeable && keys.contains(&name) {
+            segments.push(object_expr(std::mem::take(&mut props)));
+            keys.clear();
+          }
+          props.push(kv_prop(&name, value));
+          keys.insert(name);
+        }
+      }
+    }
+    if !props.is_empty() {
+      segments.push(object_expr(props));
+    }
+    let props = match segments.len() {
+      0 => Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+      1 => segments.pop().unwrap(),
+      _ => self.call_runtime("mergeProps", segments),
+    };
+
+    let mut args = vec![tag, props];
+    if native_tag.is_some() {
+      if let Some(children) = self.transform_children(el.children) {
+        args.push(children);
+      }
+    } else if let Some(slots) = self.build_slots(el.children, v_slots) {
+      args.push(slots);
+    }
+    let vnode = self.call_runtime("createVNode", args);
+    if directives.is_empty() {
+      return vnode;
+    }
+    self.call_runtime("withDirectives", vec![vnode, array_expr(directives)])
+  }
+
+  /// `<></>` -> `createVNode(Fragment, null, [...])`
+  fn transform_fragment(&mut self, fragment: JSXFragment) -> Expr {
+    let mut args = vec![self.runtime("Fragment"), Expr::Lit(Lit::Null(Null { span: DUMMY_SP }))];
+    args.extend(self.transform_children(fragment.children));
+    self.call_runtime("createVNode", args)
+  }
+
+  /// Transform the children of a native element or a fragment to an array.
+  fn transform_children(&mut self, children: Vec<JSXElementChild>) -> Option<Expr> {
+    let mut elems = vec![];
+    for child in children {
+      match child {
+        JSXElementChild::JSXText(text) => {
+          if let Some(text) = jsx_text(&text.value) {
+            elems.push(Some(self.call_runtime("createTextVNode", vec![str_expr(&text)]).as_arg()));
+          }
+        }
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::Expr(expr), ..
+        }) => elems.push(Some(self.transform_expr(expr).as_arg())),
+        JSXElementChild::JSXExprContainer(_) => {}
+        JSXElementChild::JSXSpreadChild(spread) => elems.push(Some(ExprOrSpread {
+          spread: Some(DUMMY_SP),
+          expr: Box::new(self.transform_expr(spread.expr)),
+        })),
+        JSXElementChild::JSXElement(el) => elems.push(Some(self.transform_element(*el).as_arg())),
+        JSXElementChild::JSXFragment(fragment) => elems.push(Some(self.transform_fragment(fragment).as_arg())),
+      }
+    }
+    if elems.is_empty() {
+      return None;
+    }
+    Some(Expr::Array(ArrayLit { span: DUMMY_SP, elems }))
+  }
+
+  /// Build the slots object of a component, the children are the `default` slot:
+  /// - `<Comp>{{ default: () => "a", foo: () => "b" }}</Comp>` uses the object as the slots
+  /// - `<Comp>{() => "a"}</Comp>` -> `{ default: () => "a" }`
+  /// - `<Comp>{slots}</Comp>` -> `_isSlot(slots) ? slots : { default: () => [slots] }`
+  /// - `<Comp v-slots={slots}>a</Comp>` -> `{ default: () => [createTextVNode("a")], ...slots }`
+  fn build_slots(&mut self, children: Vec<JSXElementChild>, v_slots: Option<Expr>) -> Option<Expr> {
+    let mut children = children
+      .into_iter()
+      .filter(|child| match child {
+        JSXElementChild::JSXText(text) => jsx_text(&text.value).is_some(),
+        JSXElementChild::JSXExprContainer(JSXExprContainer {
+          expr: JSXExpr::JSXEmptyExpr(_),
+          ..
+        }) => false,
+        _ => true,
+      })
+      .collect::<Vec<_>>();
+    let default_slot = if children.len() == 1
+      && v_slots.is_none()
+      && let JSXElementChild::JSXExprContainer(JSXExprContainer {
+        expr: JSXExpr::Expr(expr), ..
+      }) = &children[0]
+      && !matches!(&**expr, Expr::JSXElement(_) | Expr::JSXFragment(_) | Expr::Lit(_) | Expr::Tpl(_))
+    {
+      let Some(JSXElementChild::JSXExprContainer(JSXExprContainer {
+        expr: JSXExpr::Expr(expr), ..
+      })) = children.pop()
+      else {
+        unreachable!()
+      };
+      let expr = self.transform_expr(expr);
+      return Some(match expr {
+        Expr::Object(_) => expr,
+        Expr::Arrow(_) | Expr::Fn(_) => object_expr(vec![kv_prop("default", expr)]),
+        Expr::Ident(_) => {
+          let is_slot = self.is_slot_helper();
+          let default_slot = object_expr(vec![kv_prop(
+            "default",
+            array_expr(vec![expr.clone()]).into_lazy_arrow(vec![]).into(),
+          )]);
+          Expr::Cond(CondExpr {
+            span: DUMMY_SP,
+            test: Box::new(is_slot.as_call(DUMMY_SP, vec![expr.clone().as_arg()])),
+            cons: Box::new(expr),
+            alt: Box::new(default_slot),
+          })
+        }
+        _ => object_expr(vec![kv_prop("default", array_expr(vec![expr]).into_lazy_arrow(vec![]).into())]),
+      });
+    } else {
+      self.transform_children(children)
+    };
+    let mut props = vec![];
+    if let Some(children) = default_slot {
+      props.push(kv_prop("default", children.into_lazy_arrow(vec![]).into()));
+    }
+    match v_slots {
+      Some(v_slots) if props.is_empty() => return Some(v_slots),
+      Some(v_slots) => props.push(PropOrSpread::Spread(SpreadElement {
+        dot3_token: DUMMY_SP,
+        expr: Box::new(v_slots),
+      })),
+      None if props.is_empty() => return None,
+      None => {}
+    }
+    Some(object_expr(props))
+  }
+
+  fn is_slot_helper(&mut self) -> Expr {
+    if self.is_slot.is_none() {
+      self.is_slot = Some(private_ident!("_isSlot"));
+    }
+    Expr::Ident(self.is_slot.clone().unwrap())
+  }
+}
+
+impl VisitMut for VueJsx {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module(&mut self, module: &mut Module) {
+    module.visit_mut_children_with(self);
+    let is_slot = self.is_slot.clone().map(|is_slot| {
+      // function _isSlot(s) { ... }
+      let is_vnode = self.runtime("isVNode");
+      quote!(
+        "function $is_slot(s) { return typeof s === \"function\" || (Object.prototype.toString.call(s) === \"[object Object]\" && !$is_vnode(s)); }"
+          as Stmt,
+        is_slot = is_slot,
+        is_vnode: Expr = is_vnode,
+      )
+    });
+    if self.runtime.is_empty() {
+      return;
+    }
+    let mut items = vec![ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+      span: DUMMY_SP,
+      specifiers: self
+        .runtime
+        .iter()
+        .map(|(name, local)| {
+          ImportSpecifier::Named(ImportNamedSpecifier {
+            span: DUMMY_SP,
+            local: local.clone(),
+            imported: Some(ModuleExportName::Ident(quote_ident!(*name).into())),
+            is_type_only: false,
+          })
+        })
+        .collect(),
+      src: Box::new(self.module_name.as_str().into()),
+      type_only: false,
+      with: None,
+      phase: Default::default(),
+    }))];
+    items.extend(is_slot.map(ModuleItem::Stmt));
+    let pos = module
+      .body
+      .iter()
+      .position(|item| !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+      .unwrap_or(module.body.len());
+    module.body.splice(pos..pos, items);
+  }
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    match expr {
+      Expr::JSXElement(_) => {
+        let Expr::JSXElement(el) = expr.take() else { unreachable!() };
+        *expr = self.transform_element(*el);
+      }
+      Expr::JSXFragment(_) => {
+        let Expr::JSXFragment(fragment) = expr.take() else { unreachable!() };
+        *expr = self.transform_fragment(fragment);
+      }
+      _ => expr.visit_mut_children_with(self),
+    }
+  }
+}
+
+struct VueHmr {
+  specifier: String,
+}
+
+impl VisitMut for VueHmr {
+  noop_visit_mut_type!();
+
+  fn visit_mut_module(&mut self, module: &mut Module) {
+    // the local names of `defineComponent` imported from vue
+    let define_component = module
+      .body
+      .iter()
+      .filter_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+          if !import.type_only && is_vue_import_source(&import.src.value.to_string_lossy()) =>
+        {
+          Some(import)
+        }
+        _ => None,
+      })
+      .flat_map(|import| import.specifiers.iter())
+      .filter_map(|specifier| match specifier {
+        ImportSpecifier::Named(named) => {
+          let imported = match &named.imported {
+            Some(ModuleExportName::Ident(id)) => id.sym.to_string(),
+            Some(ModuleExportName::Str(s)) => s.value.to_string_lossy().into_owned(),
+            None => named.local.sym.to_string(),
+          };
+          (imported == "defineComponent").then(|| named.local.to_id())
+        }
+        _ => None,
+      })
+      .collect::<HashSet<_>>();
+    if define_component.is_empty() {
+      return;
+    }
+    let is_define_component = |expr: &Expr| match expr {
+      Expr::Call(CallExpr {
+        callee: Callee::Expr(callee),
+        ..
+      }) => matches!(&**callee, Expr::Ident(id) if define_component.contains(&id.to_id())),
+      _ => false,
+    };
+
+    // the exported components, `(export name, local ident)`
+    let mut components: Vec<(String, Ident)> = vec![];
+    let mut locals: IndexMap<Id, Ident> = IndexMap::new();
+    let mut body = Vec::with_capacity(module.body.len());
+    for item in module.body.drain(..) {
+      match item {
+        // export default defineComponent({}) -> const __default__ = defineComponent({}); export default __default__
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) if is_define_component(&export.expr) => {
+          let id = private_ident!("__default__");
+          body.push(ModuleItem::Stmt(Stmt::Decl(Decl::Var(Box::new(VarDecl {
+            kind: VarDeclKind::Const,
+            decls: vec![VarDeclarator {
+              span: DUMMY_SP,
+              name: id.clone().into(),
+              init: Some(export.expr),
+              definite: false,
+            }],
+            ..Default::default()
+          })))));
+          body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+            span: export.span,
+            expr: Box::new(id.clone().into()),
+          })));
+          components.push(("default".to_owned(), id));
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+          decl: Decl::Var(ref var), ..
+        })) => {
+          for decl in &var.decls {
+            if let Pat::Ident(id) = &decl.name
+              && decl.init.as_deref().is_some_and(is_define_component)
+            {
+              components.push((id.sym.to_string(), id.id.clone()));
+            }
+          }
+          body.push(item);
+        }
+        ModuleItem::Stmt(Stmt::Decl(Decl::Var(ref var))) => {
+          for decl in &var.decls {
+            if let Pat::Ident(id) = &decl.name
+              && decl.init.as_deref().is_some_and(is_define_component)
+            {
+              locals.insert(id.to_id(), id.id.clone());
+            }
+          }
+          body.push(item);
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(ref export)) if export.src.is_none() => {
+          for specifier in &export.specifiers {
+            if let ExportSpecifier::Named(named) = specifier
+              && let ModuleExportName::Ident(local) = &named.orig
+              && let Some(id) = locals.get(&local.to_id())
+            {
+              let name = match &named.exported {
+                Some(ModuleExportName::Ident(id)) => id.sym.to_string(),
+                Some(ModuleExportName::Str(s)) => s.value.to_string_lossy().into_owned(),
+                None => local.sym.to_string(),
+              };
+              components.push((name, id.clone()));
+            }
+          }
+          body.push(item);
+        }
+        _ => body.push(item),
+      }
+    }
+    module.body = body;
+    if components.is_empty() {
+      return;
+    }
+
+    let mut reloads = vec![];
+    let mut props = vec![];
+    for (name, id) in components {
+      let hmr_id = str_expr(&hash_string(&format!("{}#{}", self.specifier, name)));
+      // Comp.__hmrId = "<hash>"
+      // __VUE_HMR_RUNTIME__.createRecord("<hash>", Comp)
+      module.body.push(ModuleItem::Stmt(quote!(
+        "$id.__hmrId = $hmr_id;" as Stmt,
+        id = id.clone(),
+        hmr_id: Expr = hmr_id.clone(),
+      )));
+      module.body.push(ModuleItem::Stmt(quote!(
+        "__VUE_HMR_RUNTIME__.createRecord($hmr_id, $id);" as Stmt,
+        id = id,
+        hmr_id: Expr = hmr_id.clone(),
+      )));
+      let new_id = private_ident!(format!("__{}", name));
+      props.push(ObjectPatProp::KeyValue(KeyValuePatProp {
+        key: prop_name(&name),
+        value: Box::new(new_id.clone().into()),
+      }));
+      reloads.push(quote!(
+        "__VUE_HMR_RUNTIME__.reload($hmr_id, $new_id);" as Stmt,
+        hmr_id: Expr = hmr_id,
+        new_id = new_id,
+      ));
+    }
+    // import.meta.hot.accept(({ default: __default }) => { __VUE_HMR_RUNTIME__.reload("<hash>", __default) })
+    let accept = Expr::Arrow(ArrowExpr {
+      params: vec![Pat::Object(ObjectPat {
+        span: DUMMY_SP,
+        props,
+        optional: false,
+        type_ann: None,
+      })],
+      body: Box::new(BlockStmtOrExpr::BlockStmt(BlockStmt {
+        stmts: reloads,
+        ..Default::default()
+      })),
+      ..Default::default()
+    });
+    module.body.push(ModuleItem::Stmt(quote!(
+      "import.meta.hot.accept($accept);" as Stmt,
+      accept: Expr = accept,
+    )));
+  }
+}
+
+/// Parse the value of a directive, e.g. `v-model={[value, "arg", ["trim"]]}` or `v-model:arg={[value, ["trim"]]}`.
+fn parse_directive_value(arg: Option<&str>, value: Expr) -> (Expr, Option<String>, Vec<String>) {
+  let mut arg = arg.map(|arg| arg.to_owned());
+  let Expr::Array(ArrayLit { elems, .. }) = value else {
+    return (value, arg, vec![]);
+  };
+  let mut elems = elems.into_iter().flatten().map(|elem| *elem.expr);
+  let value = elems.next().unwrap_or_else(|| *Expr::undefined(DUMMY_SP));
+  let mut modifiers = vec![];
+  for elem in elems {
+    match elem {
+      Expr::Lit(Lit::Str(s)) if arg.is_none() => arg = Some(s.value.to_string_lossy().into_owned()),
+      Expr::Array(ArrayLit { elems, .. }) => {
+        for elem in elems.into_iter().flatten() {
+          if let Expr::Lit(Lit::Str(s)) = &*elem.expr {
+            modifiers.push(s.value.to_string_lossy().into_owned());
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  (value, arg, modifiers)
+}
+
+fn assign_target(expr: &Expr) -> Option<SimpleAssignTarget> {
+  match expr {
+    Expr::Ident(id) => Some(SimpleAssignTarget::Ident(id.clone().into())),
+    Expr::Member(member) => Some(SimpleAssignTarget::Member(member.clone())),
+    _ => None,
+  }
+}
+
+fn is_event_prop(name: &str) -> bool {
+  name.starts_with("on") && name.chars().nth(2).is_some_and(|c| c.is_ascii_uppercase())
+}
+
+fn array_expr(elems: Vec<Expr>) -> Expr {
+  Expr::Array(ArrayLit {
+    span: DUMMY_SP,
+    elems: elems.into_iter().map(|elem| Some(elem.as_arg())).collect(),
+  })
+}
@@ -0,0 +1,192 @@
+use crate::swc_compat::{Feature, Features};
+
+/// A browser version, e.g. `(16, 4)` for Safari 16.4.
+pub type Version = (u32, u32);
+
+/// The browsers of the support tables, the mobile browsers of browserslist are mapped to the desktop ones.
+#[derive(Clone, Copy, Debug)]
+enum Browser {
+  Chrome,
+  Edge,
+  Firefox,
+  Safari,
+  Opera,
+  Ios,
+  Samsung,
+  Node,
+}
+
+impl Browser {
+  fn from_name(name: &str) -> Option<Self> {
+    match name.to_lowercase().as_str() {
+      "chrome" | "and_chr" | "chromeandroid" | "android" | "chromium" => Some(Browser::Chrome),
+      "edge" => Some(Browser::Edge),
+      "firefox" | "ff" | "and_ff" | "firefoxandroid" => Some(Browser::Firefox),
+      "safari" => Some(Browser::Safari),
+      "opera" => Some(Browser::Opera),
+      "ios" | "ios_saf" => Some(Browser::Ios),
+      "samsung" => Some(Browser::Samsung),
+      "node" => Some(Browser::Node),
+      _ => None,
+    }
+  }
+
+  /// Check if the browser has the minor releases, e.g. Safari 16.4, the others only release the major versions.
+  fn has_minor_releases(self) -> bool {
+    matches!(self, Browser::Safari | Browser::Ios | Browser::Samsung | Browser::Node)
+  }
+}
+
+/// The first versions of the browsers that support the feature, in the order of `Browser`,
+/// the data is from `@babel/compat-data`.
+fn support(feature: Feature) -> [Version; 8] {
+  match feature {
+    Feature::UnicodeSetsRegex => [(112, 0), (112, 0), (116, 0), (17, 0), (98, 0), (17, 0), (23, 0), (20, 0)],
+    Feature::ClassStaticBlock => [(94, 0), (94, 0), (93, 0), (16, 4), (80, 0), (16, 4), (17, 0), (16, 11)],
+    Feature::PrivatePropertyInObject => [(91, 0), (91, 0), (90, 0), (15, 0), (77, 0), (15, 0), (16, 0), (16, 9)],
+    Feature::ClassProperties => [(84, 0), (84, 0), (90, 0), (15, 0), (70, 0), (15, 0), (14, 0), (14, 6)],
+    Feature::RegexpHasIndices => [(90, 0), (90, 0), (88, 0), (15, 0), (76, 0), (15, 0), (15, 0), (16, 0)],
+    Feature::LogicalAssignments => [(85, 0), (85, 0), (79, 0), (14, 0), (71, 0), (14, 0), (14, 0), (15, 0)],
+    Feature::OptionalChaining => [(91, 0), (91, 0), (74, 0), (13, 1), (77, 0), (13, 4), (16, 0), (16, 9)],
+    Feature::NullishCoalescing => [(80, 0), (80, 0), (72, 0), (13, 1), (67, 0), (13, 4), (13, 0), (14, 0)],
+    Feature::ExportNamespaceFrom => [(72, 0), (79, 0), (80, 0), (14, 1), (60, 0), (14, 5), (11, 0), (13, 2)],
+    Feature::OptionalCatchBinding => [(66, 0), (79, 0), (58, 0), (11, 1), (53, 0), (11, 3), (9, 0), (10, 0)],
+    Feature::ObjectRestSpread => [(60, 0), (79, 0), (55, 0), (11, 1), (47, 0), (11, 3), (8, 0), (8, 3)],
+    Feature::RegexpDotAll => [(62, 0), (79, 0), (78, 0), (11, 1), (49, 0), (11, 3), (8, 0), (8, 10)],
+    Feature::RegexpNamedGroups => [(64, 0), (79, 0), (78, 0), (11, 1), (51, 0), (11, 3), (9, 0), (10, 0)],
+    Feature::RegexpLookbehind => [(62, 0), (79, 0), (78, 0), (16, 4), (49, 0), (16, 4), (8, 0), (8, 10)],
+    Feature::RegexpUnicodeProperty => [(64, 0), (79, 0), (78, 0), (11, 1), (51, 0), (11, 3), (9, 0), (10, 0)],
+    Feature::AsyncGenerators => [(63, 0), (79, 0), (57, 0), (12, 0), (50, 0), (12, 0), (8, 0), (10, 0)],
+    Feature::AsyncToGenerator => [(55, 0), (15, 0), (52, 0), (11, 0), (42, 0), (11, 0), (6, 0), (7, 6)],
+    Feature::Exponentiation => [(52, 0), (14, 0), (52, 0), (10, 1), (39, 0), (10, 3), (6, 0), (7, 0)],
+  }
+}
+
+/// The lowest versions of the browsers to support, resolved from a browserslist query, e.g. `"chrome >= 87, safari >= 14"`,
+/// or a User-Agent, e.g. `"Mozilla/5.0 (Macintosh; ...) Chrome/120.0.0.0 Safari/537.36"`.
+///
+/// Only the version queries of the browsers are supported, the `not` queries are ignored since they only exclude browsers,
+/// and the queries that need the usage data of caniuse, e.g. `defaults`, `last 2 versions` or `> 0.5%`, are rejected.
+#[derive(Clone, Debug, Default)]
+pub struct Browsers(Vec<(Browser, Version)>);
+
+impl Browsers {
+  pub fn parse(target: &str) -> Result<Self, String> {
+    if target.contains('/') {
+      return parse_user_agent(target)
+        .map(Browsers)
+        .ok_or_else(|| "unknown User-Agent".to_owned());
+    }
+    if target.trim().is_empty() {
+      return Err("empty browserslist query".to_owned());
+    }
+    let mut browsers = Vec::new();
+    for query in target.split(',').flat_map(|q| q.split(" or ")) {
+      let query = query.trim();
+      if query.is_empty() || query.starts_with("not ") {
+        continue;
+      }
+      // the browsers of `a and b` are a subset of the browsers of any part, so the part with the highest version is used
+      let browser = query
+        .split(" and ")
+        .map(str::trim)
+        .filter(|part| !part.starts_with("not "))
+        .filter_map(parse_query)
+        .max_by_key(|(_, version)| *version)
+        .ok_or_else(|| format!("unsupported browserslist query \"{}\"", query))?;
+      browsers.push(browser);
+    }
+    if browsers.is_empty() {
+      return Err("no browsers are selected by the browserslist query".to_owned());
+    }
+    Ok(Browsers(browsers))
+  }
+
+  /// Check if all the browsers support a feature, the `support` is the first supported versions in the order of `Browser`.
+  pub fn supports(&self, support: &[Version; 8]) -> bool {
+    self.0.iter().all(|(browser, version)| *version >= support[*browser as usize])
+  }
+
+  /// The syntax features that are not supported by the browsers.
+  pub fn features(&self) -> Features {
+    Feature::ALL.into_iter().filter(|f| !self.supports(&support(*f))).collect()
+  }
+}
+
+/// Parse a query like `chrome >= 87`, `safari > 14`, `firefox 78` or `ios_saf 14.0-14.4`.
+fn parse_query(query: &str) -> Option<(Browser, Version)> {
+  let (name, rest) = query.split_once(' ')?;
+  let browser = Browser::from_name(name)?;
+  let rest = rest.trim();
+  let (op, version) = if let Some(v) = rest.strip_prefix(">=") {
+    (">=", v)
+  } else if let Some(v) = rest.strip_prefix('>') {
+    (">", v)
+  } else if let Some(v) = rest.strip_prefix("<=") {
+    ("<=", v)
+  } else if let Some(v) = rest.strip_prefix('<') {
+    ("<", v)
+  } else {
+    ("", rest)
+  };
+  // the lowest version of a range, e.g. `14.0-14.4`
+  let version = parse_version(version.trim().split('-').next()?)?;
+  match op {
+    // the older versions are included
+    "<=" | "<" => Some((browser, (0, 0))),
+    // the next minor version for `safari > 14.1`, or the next major version for `chrome > 90.0`
+    ">" if browser.has_minor_releases() => Some((browser, (version.0, version.1 + 1))),
+    ">" => Some((browser, (version.0 + 1, 0))),
+    _ => Some((browser, version)),
+  }
+}
+
+/// Parse a version like `14`, `14.1` or `16.11.0`.
+fn parse_version(version: &str) -> Option<Version> {
+  let mut parts = version.split(['.', '_']);
+  let major = parts.next()?.parse().ok()?;
+  let minor = match parts.next() {
+    Some(minor) => minor.parse().ok()?,
+    None => 0,
+  };
+  Some((major, minor))
+}
+
+/// The version after the `token` in the User-Agent, e.g. `Chrome/120.0.0.0`.
+fn ua_version(ua: &str, token: &str) -> Option<Version> {
+  let start = ua.find(token)? + token.len();
+  let end = ua[start..]
+    .find(|c: char| !c.is_ascii_digit() && c != '.' && c != '_')
+    .map_or(ua.len(), |i| start + i);
+  parse_version(&ua[start..end])
+}
+
+/// Detect the browser from the User-Agent, the browsers on iOS use the WebKit of the system.
+/// Deno and Bun support all the features.
+fn parse_user_agent(ua: &str) -> Option<Vec<(Browser, Version)>> {
+  if ua.starts_with("Deno/") || ua.starts_with("Bun/") {
+    return Some(vec![]);
+  }
+  if ua.starts_with("Node/") || ua.starts_with("Node.js/") {
+    return Some(vec![(Browser::Node, ua_version(ua, "/")?)]);
+  }
+  if ua.contains("iPhone") || ua.contains("iPad") || ua.contains("iPod") {
+    return Some(vec![(Browser::Ios, ua_version(ua, " OS ")?)]);
+  }
+  let browser = if let Some(v) = ua_version(ua, "Edg/").or_else(|| ua_version(ua, "Edge/")) {
+    (Browser::Edge, v)
+  } else if let Some(v) = ua_version(ua, "OPR/") {
+    (Browser::Opera, v)
+  } else if let Some(v) = ua_version(ua, "SamsungBrowser/") {
+    (Browser::Samsung, v)
+  } else if let Some(v) = ua_version(ua, "Firefox/") {
+    (Browser::Firefox, v)
+  } else if let Some(v) = ua_version(ua, "Chrome/").or_else(|| ua_version(ua, "Chromium/")) {
+    (Browser::Chrome, v)
+  } else if ua.contains("Safari/") {
+    (Browser::Safari, ua_version(ua, "Version/")?)
+  } else {
+    return None;
+  };
+  Some(vec![browser])
+}
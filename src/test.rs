@@ -1,5 +1,7 @@
 use super::*;
 use serde_json::json;
+use std::str::FromStr;
+use url::Url;
 
 fn transform(filename: &str, source: &str, options: &EmitOptions) -> (String, Option<String>, Rc<RefCell<Resolver>>) {
   let importmap = import_map::parse_from_value(
@@ -14,15 +16,10 @@ fn transform(filename: &str, source: &str, options: &EmitOptions) -> (String, Op
   )
   .expect("could not pause the import map");
   let module = SWC::parse(filename, source, None).expect("could not parse module");
-  let resolver = Rc::new(RefCell::new(Resolver::new(filename, Some(importmap.import_map))));
-  let (code, source_map) = module.transform(resolver.clone(), options).unwrap();
+  let resolver = Rc::new(RefCell::new(Resolver::new(filename, Some(Rc::new(importmap.import_map)))));
+  let EmitOutput { code, map: source_map, .. } = module.transform(resolver.clone(), options).unwrap();
   let code = unsafe { std::str::from_utf8_unchecked(&code).to_string() };
-  let source_map = if let Some(source_map) = source_map {
-    Some(unsafe { std::str::from_utf8_unchecked(&source_map).to_string() })
-  } else {
-    None
-  };
-  println!("{}", code);
+  let source_map = source_map.map(|source_map| unsafe { std::str::from_utf8_unchecked(&source_map).to_string() });
   (code, source_map, resolver)
 }
 
@@ -116,6 +113,33 @@ fn module_analyzer() {
   assert!(code.contains("    type: \"css\""));
 }
 
+#[test]
+fn deps() {
+  let source = r#"import React from "react"
+export { foo } from "~/foo.ts"
+export * from "./bar.ts"
+import data from "/data.json" with { type: "json" }
+import("react")
+"#;
+  let (_, _, resolver) = transform("/foo/index.js", source, &EmitOptions::default());
+  let deps = &resolver.borrow().deps;
+  assert_eq!(deps.len(), 5);
+  assert_eq!(deps[0].specifier, "react");
+  assert_eq!(deps[0].resolved_url, "https://esm.sh/react");
+  assert_eq!(deps[0].kind, resolver::DependencyKind::Static);
+  assert_eq!(deps[0].span, Some(resolver::DependencySpan { start: 0, end: 25 }));
+  assert_eq!(deps[1].specifier, "~/foo.ts");
+  assert_eq!(deps[1].resolved_url, "/foo.ts");
+  assert_eq!(deps[1].kind, resolver::DependencyKind::ReExport);
+  assert_eq!(deps[2].resolved_url, "./bar.ts");
+  assert_eq!(deps[2].kind, resolver::DependencyKind::ExportAll);
+  assert_eq!(deps[3].kind, resolver::DependencyKind::Static);
+  assert_eq!(deps[3].attributes.as_ref().unwrap().get("type").unwrap(), "json");
+  assert_eq!(deps[4].kind, resolver::DependencyKind::Dynamic);
+  assert_eq!(deps[4].resolved_url, "https://esm.sh/react");
+  assert!(deps[4].attributes.is_none());
+}
+
 #[test]
 fn tsx() {
   let source = r#"
@@ -139,7 +163,126 @@ fn tsx() {
   assert!(code.contains("_jsx(_Fragment, {"));
   assert!(code.contains("_jsx(\"h1\", {"));
   assert!(code.contains("children: \"Hello world!\""));
-  assert_eq!(resolver.borrow().deps.get(0).unwrap().0, "https://esm.sh/react@18/jsx-runtime");
+  assert_eq!(
+    resolver.borrow().deps.first().unwrap().specifier,
+    "https://esm.sh/react@18/jsx-runtime"
+  );
+}
+
+#[test]
+fn compiled_import_map() {
+  let import_map = CompiledImportMap::parse(json!({
+    "$src": "/index.html",
+    "imports": {
+      "preact": "https://esm.sh/preact",
+      "preact/": "https://esm.sh/preact/",
+      "lodash": 1
+    },
+    "scopes": {
+      "/islands/": {
+        "react/jsx-runtime": "https://esm.sh/react/jsx-runtime"
+      }
+    }
+  }))
+  .expect("could not parse the import map");
+  assert_eq!(import_map.diagnostics().len(), 1);
+  assert_eq!(import_map.diagnostics()[0].filename, "file:///index.html");
+  assert_eq!(import_map.jsx_import_source("/app.tsx", false), "preact");
+  assert_eq!(import_map.jsx_import_source("/islands/counter.tsx", false), "react");
+  assert_eq!(import_map.jsx_import_source("/pages/index.tsx", false), "preact");
+  assert_eq!(import_map.jsx_import_source("/pages/index.tsx", true), "preact");
+  assert!(CompiledImportMap::parse(json!({ "$src": "::" })).is_err());
+  assert!(CompiledImportMap::parse(json!([])).is_err());
+}
+
+#[test]
+fn native_api() {
+  let output = crate::transform(&SWCTransformOptions {
+    filename: "/app.tsx".to_owned(),
+    code: b"import { h } from \"preact\"\nconsole.log(h)\nexport default (props: {}) => <div />".to_vec(),
+    import_map: Some(json!({
+      "imports": {
+        "preact": "https://esm.sh/preact",
+        "preact/jsx-runtime": "https://esm.sh/preact/jsx-runtime"
+      }
+    })),
+    ..Default::default()
+  })
+  .expect("could not transform the module");
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("import { jsx as _jsx } from \"https://esm.sh/preact/jsx-runtime\""));
+  assert!(code.contains("import { h } from \"https://esm.sh/preact\""));
+  assert_eq!(output.deps.len(), 2);
+
+  let err = crate::transform(&SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: b"export App() {}".to_vec(),
+    ..Default::default()
+  })
+  .err()
+  .expect("should fail to parse");
+  assert_eq!(err.to_string(), "Expected '{', got 'ident' at /app.ts:1:7");
+  assert_eq!(err.diagnostics.len(), 1);
+}
+
+#[test]
+fn input_encoding() {
+  let transform_bytes = |code: Vec<u8>| {
+    crate::transform(&SWCTransformOptions {
+      filename: "/app.ts".to_owned(),
+      code,
+      ..Default::default()
+    })
+  };
+  let source = "const s: string = \"héllo\"";
+  let expected = "const s = \"héllo\";\n";
+
+  let code = [&[0xEF, 0xBB, 0xBF], source.as_bytes()].concat();
+  assert_eq!(String::from_utf8(transform_bytes(code).unwrap().code).unwrap(), expected);
+
+  let code = [0xFF, 0xFE]
+    .into_iter()
+    .chain(source.encode_utf16().flat_map(|u| u.to_le_bytes()))
+    .collect();
+  assert_eq!(String::from_utf8(transform_bytes(code).unwrap().code).unwrap(), expected);
+
+  let code = [0xFE, 0xFF]
+    .into_iter()
+    .chain(source.encode_utf16().flat_map(|u| u.to_be_bytes()))
+    .collect();
+  assert_eq!(String::from_utf8(transform_bytes(code).unwrap().code).unwrap(), expected);
+
+  // the spans point to the raw UTF-16 bytes
+  let code = [0xFF, 0xFE]
+    .into_iter()
+    .chain("// é\nimport \"./a.ts\";".encode_utf16().flat_map(|u| u.to_le_bytes()))
+    .collect();
+  let output = transform_bytes(code).unwrap();
+  assert_eq!(output.deps[0].span, Some(DependencySpan { start: 12, end: 44 }));
+  let code = [&[0xEF, 0xBB, 0xBF], "import \"./a.ts\";".as_bytes()].concat();
+  let output = transform_bytes(code).unwrap();
+  assert_eq!(output.deps[0].span, Some(DependencySpan { start: 3, end: 19 }));
+
+  let err = transform_bytes(b"const a = 1\nconst s = \"h\xFFllo\"".to_vec()).err().unwrap();
+  assert_eq!(err.to_string(), "Invalid UTF-8 sequence (byte offset 24) at /app.ts:2:12");
+  assert_eq!(err.diagnostics[0].span, Some(error::DiagnosticSpan { start: 24, end: 25 }));
+
+  let code = [0xFF, 0xFE, b'a', 0, 0x00, 0xD8, b'b', 0].to_vec();
+  let err = transform_bytes(code).err().unwrap();
+  assert_eq!(
+    err.diagnostics[0].message,
+    "Invalid UTF-16 sequence, unpaired surrogate (byte offset 4)"
+  );
+}
+
+#[test]
+fn shared_globals() {
+  swc_common::GLOBALS.set(&swc_common::Globals::new(), || {
+    for (filename, source) in [("/a.ts", "export const a: number = 1"), ("/b.tsx", "export default () => <div />")] {
+      let (code, _, _) = transform(filename, source, &EmitOptions::default());
+      assert!(code.starts_with("export"));
+    }
+  });
 }
 
 #[test]
@@ -233,6 +376,23 @@ fn tree_shaking() {
   assert_eq!(code, "import \"https://esm.sh/react\";\n");
 }
 
+#[test]
+fn diagnostics() {
+  let err = SWC::parse("/source.ts", "const a = 1\nexport App() {}", None)
+    .err()
+    .expect("should fail to parse");
+  assert_eq!(err.to_string(), "Expected '{', got 'ident' at /source.ts:2:7");
+  assert_eq!(err.0.len(), 1);
+  let diagnostic = &err.0[0];
+  assert_eq!(diagnostic.message, "Expected '{', got 'ident'");
+  assert_eq!(diagnostic.severity, error::Severity::Error);
+  assert_eq!(diagnostic.filename, "/source.ts");
+  assert_eq!(diagnostic.start, Some(error::Position { line: 2, column: 7 }));
+  assert_eq!(diagnostic.end, Some(error::Position { line: 2, column: 10 }));
+  assert_eq!(diagnostic.span, Some(error::DiagnosticSpan { start: 19, end: 22 }));
+  assert_eq!(diagnostic.code_frame.as_deref(), Some("2 | export App() {}\n  |        ^^^"));
+}
+
 #[test]
 fn source_map() {
   let source = r#"
@@ -265,3 +425,1064 @@ fn source_map() {
       .contains("\"sourcesContent\":[\"\\n    const foo:string = \\\"bar\\\"\\n  \"]")
   );
 }
+
+#[test]
+fn recovered_errors() {
+  let module = SWC::parse("/app.js", "const a;\nexport default a", None).expect("should recover from the error");
+  assert_eq!(module.recovered_errors.len(), 1);
+  assert_eq!(
+    module.recovered_errors[0].to_string(),
+    "'const' declarations must be initialized at /app.js:1:6"
+  );
+
+  let err = SWC::parse("/app.js", "const a;\nexport App() {}", None)
+    .err()
+    .expect("should fail to parse");
+  assert_eq!(err.0.len(), 2);
+  assert_eq!(err.0[0].message, "'const' declarations must be initialized");
+  assert_eq!(err.0[1].start.unwrap().line, 2);
+}
+
+#[test]
+fn unresolved_bare_specifier() {
+  let source = r#"
+    import React from "react"
+    import { debounce } from "lodash"
+    import "./style.css"
+  "#;
+  let (_, _, resolver) = transform("/app.js", source, &EmitOptions::default());
+  let warnings = &resolver.borrow().warnings;
+  assert_eq!(warnings.len(), 1);
+  assert_eq!(warnings[0].severity, error::Severity::Warning);
+  assert_eq!(
+    warnings[0].to_string(),
+    "Unresolved bare specifier \"lodash\", it's not found in the import map at /app.js:3:4"
+  );
+}
+
+#[test]
+fn decorators() {
+  let source = r#"
+    function logged(value, { kind, name, metadata, addInitializer }) {
+      metadata[name] = kind;
+      addInitializer(function () {});
+      return value;
+    }
+
+    @logged
+    export class A extends B {
+      @logged accessor count = 0;
+      @logged greet() {}
+    }
+  "#;
+  let (code, _, _) = transform("/test.js", source, &EmitOptions::default());
+  assert!(code.contains("_apply_decs_2203_r(this"));
+  // the parent class is passed to the helper to inherit the `Symbol.metadata`
+  assert!(code.contains("], _B));"));
+  assert!(!code.contains("@logged"));
+
+  let (code, _, _) = transform(
+    "/test.ts",
+    "@logged class A { @logged accessor count: number = 0 }",
+    &EmitOptions {
+      decorators: Some(DecoratorsVersion::V202311),
+      ..Default::default()
+    },
+  );
+  assert!(code.contains("_apply_decs_2203_r(this"));
+
+  let (code, _, _) = transform("/test.ts", "class A { @logged greet() {} }", &EmitOptions::default());
+  assert!(code.contains("_ts_decorate(["));
+
+  let (code, _, _) = transform(
+    "/test.js",
+    "@logged class A { @logged accessor count = 0 }",
+    &EmitOptions {
+      decorators: Some(DecoratorsVersion::None),
+      ..Default::default()
+    },
+  );
+  assert!(code.contains("@logged"));
+  assert!(code.contains("accessor count = 0"));
+
+  let options = SWCTransformOptions {
+    filename: "/test.ts".to_owned(),
+    code: b"@logged class A {}".to_vec(),
+    decorators: Some("2023-11".to_owned()),
+    ..Default::default()
+  };
+  let code = String::from_utf8(crate::transform(&options).unwrap().code).unwrap();
+  assert!(code.contains("_apply_decs_2203_r(this"));
+}
+
+#[test]
+fn emit_decorator_metadata() {
+  let source = r#"
+    import { Injectable, Inject } from "di";
+    import { Logger } from "./logger.ts";
+
+    @Injectable()
+    export class Service {
+      constructor(private logger: Logger, @Inject("config") config: Record<string, string>) {}
+      @Inject() name: string;
+      @Inject() render(el: HTMLElement): JSX.Element { return <div /> }
+    }
+  "#;
+  let options = EmitOptions {
+    emit_decorator_metadata: true,
+    ..Default::default()
+  };
+  let (code, _, _) = transform("/test.tsx", source, &options);
+  // the types referenced by the metadata are kept as values
+  assert!(code.contains("import { Logger } from \"/logger.ts\";"));
+  assert!(code.contains("_ts_metadata(\"design:paramtypes\", [\n        typeof Logger === \"undefined\" ? Object : Logger,"));
+  assert!(code.contains("_ts_metadata(\"design:type\", String)"));
+  assert!(code.contains("_ts_metadata(\"design:returntype\""));
+  assert!(code.contains("_ts_param(1, Inject(\"config\"))"));
+
+  let (code, _, _) = transform("/test.tsx", source, &EmitOptions::default());
+  assert!(!code.contains("_ts_metadata"));
+  assert!(!code.contains("/logger.ts"));
+}
+
+#[test]
+fn tsconfig() {
+  let source = r#"
+    import type { Props } from "@/types.ts";
+    import { Button } from "@/components/Button.tsx";
+    import { h, Fragment } from "preact";
+    import { cn } from "utils";
+    export class A { @dec foo = 1 }
+    export default (props: Props) => <><Button class={cn()} /></>
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/src/app.tsx".to_owned(),
+    code: source.as_bytes().to_vec(),
+    tsconfig: Some(json!({
+      "compilerOptions": {
+        "target": "ES2020",
+        "jsx": "react",
+        "jsxFactory": "h",
+        "jsxFragmentFactory": "Fragment",
+        "experimentalDecorators": false,
+        "verbatimModuleSyntax": true,
+        "baseUrl": ".",
+        "paths": {
+          "@/*": ["./src/*"],
+          "utils": ["./src/lib/utils.ts"]
+        }
+      }
+    })),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("import { Button } from \"./components/Button.tsx\";"));
+  assert!(code.contains("import { cn } from \"./lib/utils.ts\";"));
+  assert!(!code.contains("types.ts"));
+  assert!(code.contains("h(Fragment, null, /*#__PURE__*/ h(Button, {"));
+  assert!(code.contains("_apply_decs_2203_r(A, ["));
+  assert!(output.warnings.is_empty());
+
+  // the transform options take precedence over the tsconfig
+  let options = SWCTransformOptions {
+    jsx_import_source: Some("preact".to_owned()),
+    decorators: Some("legacy".to_owned()),
+    target: Some("es2022".to_owned()),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("from \"preact/jsx-runtime\""));
+  assert!(code.contains("_ts_decorate(["));
+
+  let options = SWCTransformOptions {
+    tsconfig: Some(json!({ "compilerOptions": { "target": "ES5", "jsx": "solid" } })),
+    target: None,
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  assert_eq!(output.warnings.len(), 2);
+  assert!(output.warnings[0].message.starts_with("Unsupported jsx \"solid\""));
+  assert_eq!(
+    output.warnings[1].message,
+    "Unsupported target \"ES5\" in tsconfig, using \"es2015\""
+  );
+  // the syntax is lowered for es2015
+  assert!(output.lowered_features.contains(&"class-properties".to_owned()));
+}
+
+#[test]
+fn use_define_for_class_fields() {
+  let transform = |tsconfig: serde_json::Value| {
+    let options = SWCTransformOptions {
+      filename: "/app.ts".to_owned(),
+      code: b"export class A { x: number; y = 1 }".to_vec(),
+      tsconfig: Some(tsconfig),
+      ..Default::default()
+    };
+    String::from_utf8(crate::transform(&options).unwrap().code).unwrap()
+  };
+  // the `[[Define]]` semantics is the default for the ES2022 and later targets like TypeScript
+  let code = transform(json!({ "compilerOptions": { "target": "ES2022" } }));
+  assert!(code.contains("    x;\n    y = 1;"));
+  let code = transform(json!({ "compilerOptions": { "target": "ES2020" } }));
+  assert!(code.contains("this.y = 1;"));
+  assert!(!code.contains("x;"));
+  let code = transform(json!({ "compilerOptions": { "target": "ESNext", "useDefineForClassFields": false } }));
+  assert!(code.contains("this.y = 1;"));
+
+  // only the public fields are assigned with the `[[Set]]` semantics, the private fields are kept
+  let options = SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: b"export class B extends A { #x = 1; y = this.#x; static z = 2; static { console.log(B.z); } }".to_vec(),
+    tsconfig: Some(json!({ "compilerOptions": { "target": "ESNext", "useDefineForClassFields": false } })),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  println!("{}", code);
+  assert!(code.contains("    #x = 1;\n"));
+  assert!(code.contains("constructor(...args){\n        super(...args), this.y = this.#x;\n    }"));
+  assert!(code.contains("static{\n        this.z = 2;\n    }\n    static{\n        console.log(B.z);\n    }"));
+  assert!(!code.contains("WeakMap"));
+  assert!(output.lowered_features.is_empty());
+}
+
+#[test]
+fn jsx_pragmas() {
+  let import_map = CompiledImportMap::parse(json!({
+    "imports": {
+      "react/jsx-runtime": "https://esm.sh/react/jsx-runtime",
+      "preact/jsx-runtime": "https://esm.sh/preact/jsx-runtime",
+      "preact": "https://esm.sh/preact"
+    }
+  }))
+  .unwrap();
+  let transform = |source: &str| {
+    let options = SWCTransformOptions {
+      filename: "/islands/counter.tsx".to_owned(),
+      code: source.as_bytes().to_vec(),
+      jsx_import_source: Some("react".to_owned()),
+      ..Default::default()
+    };
+    let output = crate::transform_with_import_map(&options, &import_map).unwrap();
+    String::from_utf8(output.code).unwrap()
+  };
+
+  let code = transform("/** @jsxImportSource preact */\nexport default () => <div />");
+  assert!(code.contains("import { jsx as _jsx } from \"https://esm.sh/preact/jsx-runtime\";"));
+
+  let code = transform(
+    r#"
+      // @license MIT
+      /** @jsx h */
+      /** @jsxFrag Fragment */
+      import { h, Fragment } from "preact";
+      export default () => <><div /></>
+    "#,
+  );
+  assert!(code.contains("import { h, Fragment } from \"https://esm.sh/preact\";"));
+  assert!(code.contains("h(Fragment, null, /*#__PURE__*/ h(\"div\", null))"));
+  assert!(!code.contains("jsx-runtime"));
+
+  let code = transform("/** @jsxRuntime classic */\nimport React from 'react';\nexport default () => <div />");
+  assert!(code.contains("React.createElement(\"div\", null)"));
+}
+
+#[test]
+fn jsx_runtime() {
+  let source = r#"
+    import { h, Fragment } from "preact";
+    export default () => <><div /></>
+  "#;
+  let transform = |options: SWCTransformOptions| {
+    let options = SWCTransformOptions {
+      filename: "/app.tsx".to_owned(),
+      code: source.as_bytes().to_vec(),
+      ..options
+    };
+    String::from_utf8(crate::transform(&options).unwrap().code).unwrap()
+  };
+
+  let code = transform(SWCTransformOptions {
+    jsx_runtime: Some("classic".to_owned()),
+    ..Default::default()
+  });
+  assert!(code.contains("React.createElement(React.Fragment, null"));
+  assert!(!code.contains("from \"preact\""));
+
+  let code = transform(SWCTransformOptions {
+    jsx_factory: Some("h".to_owned()),
+    jsx_fragment_factory: Some("Fragment".to_owned()),
+    ..Default::default()
+  });
+  // the imports of the pragma are kept
+  assert!(code.contains("import { h, Fragment } from \"preact\";"));
+  assert!(code.contains("h(Fragment, null, /*#__PURE__*/ h(\"div\", null))"));
+
+  let code = transform(SWCTransformOptions {
+    jsx_runtime: Some("automatic".to_owned()),
+    ..Default::default()
+  });
+  assert!(code.contains("from \"react/jsx-runtime\""));
+
+  let options = SWCTransformOptions {
+    filename: "/app.tsx".to_owned(),
+    jsx_runtime: Some("hyperscript".to_owned()),
+    ..Default::default()
+  };
+  assert_eq!(crate::transform(&options).err().unwrap().message, "Invalid jsxRuntime: hyperscript");
+}
+
+#[test]
+fn resolved_jsx_runtime() {
+  let transform = |code: &str, tsconfig: Option<serde_json::Value>| {
+    let options = SWCTransformOptions {
+      filename: "/app.tsx".to_owned(),
+      code: code.as_bytes().to_vec(),
+      tsconfig,
+      ..Default::default()
+    };
+    let output = crate::transform(&options).unwrap();
+    (String::from_utf8(output.code).unwrap(), output.jsx_runtime)
+  };
+
+  let (code, jsx_runtime) = transform("export default () => <div />", None);
+  assert!(code.contains("from \"react/jsx-runtime\""));
+  assert_eq!(jsx_runtime, "automatic");
+
+  let tsconfig = json!({ "compilerOptions": { "jsx": "preserve" } });
+  let (code, jsx_runtime) = transform("export default () => <div />", Some(tsconfig));
+  assert!(code.contains("<div/>"));
+  assert_eq!(jsx_runtime, "preserve");
+
+  let tsconfig = json!({ "compilerOptions": { "jsx": "react-native" } });
+  let (_, jsx_runtime) = transform("export default () => <div />", Some(tsconfig));
+  assert_eq!(jsx_runtime, "preserve");
+
+  let (code, jsx_runtime) = transform("/** @jsxRuntime preserve */\nexport default () => <div />", None);
+  assert!(code.contains("<div/>"));
+  assert_eq!(jsx_runtime, "preserve");
+}
+
+#[test]
+fn jsx_preserve() {
+  let source = r#"
+    import { Button } from "~/components/Button.tsx";
+    import type { Props } from "~/types.ts";
+    export default function App(props: Props) {
+      return <><Button class={props.class as string} {...props} /></>
+    }
+  "#;
+  let options = EmitOptions {
+    jsx_runtime: Some(JsxRuntime::Preserve),
+    source_map: Some("external".to_owned()),
+    dev: Some(DevOptions::default()),
+    ..Default::default()
+  };
+  let (code, source_map, _) = transform("/app.tsx", source, &options);
+  assert!(code.contains("import { Button } from \"/components/Button.tsx\";"));
+  assert!(code.contains("return <><Button class={props.class} {...props}/></>;"));
+  assert!(!code.contains("jsx-runtime"));
+  assert!(!code.contains("__self"));
+  assert!(!code.contains("displayName"));
+  assert!(!code.contains("__PURE__"));
+  assert!(source_map.unwrap().contains("\"mappings\":"));
+
+  // the output can be parsed as JSX
+  assert!(SWC::parse("/app.jsx", &code, None).is_ok());
+}
+
+#[test]
+fn solid_jsx() {
+  let source = r#"
+    import { createSignal, For } from "solid-js";
+    export default function App(props: { name: string; items: string[] }) {
+      const [count, setCount] = createSignal(0);
+      return (
+        <div class="app" title={props.name}>
+          <h1>Hello {props.name}!</h1>
+          <button onClick={() => setCount(count() + 1)}>Count: {count()}</button>
+          <For each={props.items}>{(item) => <li>{item}</li>}</For>
+        </div>
+      );
+    }
+  "#;
+  let options = EmitOptions {
+    jsx_import_source: Some("solid-js".to_owned()),
+    ..Default::default()
+  };
+  let (code, _, _) = transform("/app.tsx", source, &options);
+  assert!(code.contains("} from \"solid-js/web\";"));
+  assert!(code.contains(r#"_$template('<div class="app"><h1>Hello <!>!</h1><button>Count: </button></div>')"#));
+  assert!(code.contains("_$effect(()=>_$setAttribute(_el$, \"title\", props.name));"));
+  assert!(code.contains("_$insert(_el$1, ()=>props.name, _el$"));
+  assert!(code.contains(".$$click = ()=>setCount(count() + 1);"));
+  assert!(code.contains("_$insert(_el$2, ()=>count(), null);"));
+  assert!(code.contains("_$createComponent(For, {\n"));
+  assert!(code.contains("children: (item)=>"));
+  assert!(code.contains("_$delegateEvents([\n    \"click\"\n]);"));
+  assert!(!code.contains("jsx-runtime"));
+
+  let options = EmitOptions {
+    jsx_import_source: Some("https://esm.sh/solid-js@1.9.0".to_owned()),
+    solid: SolidOptions {
+      generate: Some(SolidGenerate::Ssr),
+      ..Default::default()
+    },
+    ..Default::default()
+  };
+  let (code, _, _) = transform("/app.tsx", source, &options);
+  assert!(code.contains("} from \"https://esm.sh/solid-js@1.9.0/web\";"));
+  assert!(code.contains("_tmpl$ = [\n    \"<li>\",\n    \"</li>\"\n]"));
+  assert!(code.contains("'<div class=\"app\" ',\n    \"><h1>Hello \",\n    \"!</h1><button>Count: \","));
+  assert!(code.contains("children: (item)=>_$ssr(_tmpl$, _$escape(item))"));
+  assert!(code.contains("_$ssrAttribute(\"title\", _$escape(props.name, true), false)"));
+  assert!(code.contains("_$escape(count())"));
+  assert!(!code.contains("$$click"));
+
+  // the jsx import source is detected from the import map
+  let options = SWCTransformOptions {
+    filename: "/app.jsx".to_owned(),
+    code: b"export default () => <p>{Date.now()}</p>".to_vec(),
+    import_map: Some(json!({
+      "imports": {
+        "solid-js": "https://esm.sh/solid-js",
+        "solid-js/": "https://esm.sh/solid-js/"
+      }
+    })),
+    ..Default::default()
+  };
+  let code = String::from_utf8(crate::transform(&options).unwrap().code).unwrap();
+  assert!(code.contains("from \"https://esm.sh/solid-js/web\";"));
+  assert!(code.contains("_$template(\"<p></p>\")"));
+
+  // the output mode is validated
+  let options = serde_json::from_value::<SWCTransformOptions>(json!({
+    "filename": "/app.jsx",
+    "code": [],
+    "solid": { "generate": "html" }
+  }));
+  assert!(
+    options
+      .err()
+      .unwrap()
+      .to_string()
+      .contains("unknown variant `html`, expected `dom` or `ssr`")
+  );
+}
+
+#[test]
+fn vue_jsx() {
+  let source = r#"
+    import { defineComponent, ref } from "vue";
+    import Child from "./Child.tsx";
+    export default defineComponent((props: { class: string }) => {
+      const text = ref("");
+      const visible = ref(true);
+      return () => (
+        <div class="app" {...props} class={{ active: visible.value }}>
+          <input v-model={text.value} type="checkbox" />
+          <p v-show={visible.value} onClick={() => (visible.value = false)}>hello</p>
+          <Child v-model:title={text.value}>{{ default: () => "x", foo: () => <span /> }}</Child>
+          <Child>text</Child>
+        </div>
+      );
+    });
+  "#;
+  let options = EmitOptions {
+    jsx_import_source: Some("vue".to_owned()),
+    dev: Some(DevOptions {
+      hmr: Some(HmrOptions {
+        runtime: "/_hmr.js".to_owned(),
+      }),
+      ..Default::default()
+    }),
+    ..Default::default()
+  };
+  let (code, _, _) = transform("/app.tsx", source, &options);
+  assert!(code.contains("import { mergeProps as _mergeProps, "));
+  assert!(code.contains("} from \"vue\";"));
+  assert!(code.contains("_createVNode(\"div\", _mergeProps({\n"));
+  assert!(code.contains("\"onUpdate:modelValue\": ($event)=>text.value = $event,"));
+  assert!(code.contains("_vModelCheckbox,\n"));
+  assert!(code.contains("_vShow,\n"));
+  assert!(code.contains("onClick: ()=>visible.value = false"));
+  assert!(code.contains("title: text.value,\n"));
+  assert!(code.contains("\"onUpdate:title\": ($event)=>text.value = $event"));
+  assert!(code.contains("default: ()=>\"x\",\n"));
+  assert!(code.contains("default: ()=>[\n"));
+  assert!(code.contains("_createTextVNode(\"text\")"));
+  assert!(!code.contains("jsx-runtime"));
+
+  // hmr
+  assert!(code.contains("const __default__ = defineComponent("));
+  assert!(code.contains("export default __default__;"));
+  assert!(code.contains("__default__.__hmrId = \""));
+  assert!(code.contains("__VUE_HMR_RUNTIME__.createRecord(\""));
+  assert!(code.contains("import.meta.hot.accept(({ default: __default })=>{"));
+}
+
+#[test]
+fn jsx_precompile() {
+  let source = r#"
+    import Card from "./Card.tsx";
+    export default function App({ name, items }: { name: string; items: string[] }) {
+      return (
+        <div class={name ? "a" : "b"} data-x="1">
+          <h1 className="title">Hello {name} &amp; <b>you</b></h1>
+          <a href={`/u/${name}`}>link</a>
+          <input disabled value={5} />
+          <Card title="x"><p>inside</p></Card>
+          <ul>{items.map((item) => <li key={item}>{item}</li>)}</ul>
+        </div>
+      );
+    }
+  "#;
+  let options = EmitOptions {
+    jsx_runtime: Some(JsxRuntime::Precompile),
+    jsx_import_source: Some("preact".to_owned()),
+    jsx_precompile_skip_elements: vec!["a".to_owned()],
+    ..Default::default()
+  };
+  let (code, _, _) = transform("/app.tsx", source, &options);
+  assert!(code.contains(
+    "import { jsxAttr as _jsxAttr, jsxEscape as _jsxEscape, jsx as _jsx, jsxTemplate as _jsxTemplate } from \"preact/jsx-runtime\";"
+  ));
+  assert!(code.contains("$$_tpl_1 = [\n    \"<p>inside</p>\"\n]"));
+  assert!(code.contains(
+    "\"<div \",\n    ' data-x=\"1\"><h1 class=\"title\">Hello ',\n    \" &amp; <b>you</b></h1>\",\n    '<input disabled value=\"5\">',"
+  ));
+  assert!(code.contains("_jsxTemplate($$_tpl_2, _jsxAttr(\"class\", name ? \"a\" : \"b\"), _jsxEscape(name), _jsx(\"a\", {"));
+  assert!(code.contains("children: _jsxTemplate($$_tpl_1)"));
+  assert!(code.contains("_jsxEscape(items.map((item)=>_jsx(\"li\", {\n"));
+  assert!(!code.contains("jsxs"));
+
+  // react does not provide the precompile runtime, the import source is required
+  let options = SWCTransformOptions {
+    filename: "/app.tsx".to_owned(),
+    code: b"export default () => <p>hello</p>".to_vec(),
+    jsx_runtime: Some("precompile".to_owned()),
+    ..Default::default()
+  };
+  assert_eq!(
+    crate::transform(&options).err().unwrap().message,
+    "The precompile jsxRuntime requires the jsxImportSource, e.g. \"preact\""
+  );
+  let module = SWC::parse("/app.tsx", "export default () => <p>hello</p>", None).unwrap();
+  let resolver = Rc::new(RefCell::new(Resolver::new("/app.tsx", None)));
+  let options = EmitOptions {
+    jsx_runtime: Some(JsxRuntime::Precompile),
+    ..Default::default()
+  };
+  assert!(module.transform(resolver, &options).is_err());
+}
+
+#[test]
+fn compat() {
+  let source = r#"
+    const re = /[\p{L}--[a-z]]/v;
+    class A {
+      x = 1;
+      #y = 2;
+      static { console.log("static"); }
+      has(o: object) { return #y in o; }
+    }
+    let a: any = {};
+    a.b ??= 1;
+    console.log(a?.b ?? "b");
+    try { JSON.parse("") } catch { }
+    const { b, ...rest } = a;
+    async function f() { await 1; }
+    console.log(2 ** 3);
+  "#;
+  let cases = [
+    (EsVersion::Es2024, "const re = /[\\p{L}--[a-z]]/v;"),
+    (EsVersion::Es2023, "const re = RegExp(\"[\\\\p{L}--[a-z]]\", \"v\");"),
+    (EsVersion::Es2022, "    #y = 2;\n"),
+    (EsVersion::Es2021, "_class_private_field_init(this, _y, {"),
+    (EsVersion::Es2021, "a.b ??= 1;"),
+    (EsVersion::Es2020, "(_a = a).b ?? (_a.b = 1);"),
+    (EsVersion::Es2020, "console.log(a?.b ?? \"b\");"),
+    (EsVersion::Es2019, "a === null || a === void 0 ? void 0 : a.b"),
+    (EsVersion::Es2019, "} catch  {}"),
+    (EsVersion::Es2018, "} catch (unused) {}"),
+    (EsVersion::Es2018, "const { b, ...rest } = a;"),
+    (EsVersion::Es2017, "rest = _object_without_properties(a, ["),
+    (EsVersion::Es2017, "async function f() {"),
+    (EsVersion::Es2016, "return _async_to_generator(function*() {"),
+    (EsVersion::Es2016, "console.log(2 ** 3);"),
+    (EsVersion::Es2015, "console.log(Math.pow(2, 3));"),
+  ];
+  for (target, expected) in cases {
+    let options = EmitOptions {
+      target,
+      ..Default::default()
+    };
+    let (code, _, _) = transform("/app.ts", source, &options);
+    assert!(code.contains(expected), "{:?}: {}", target, expected);
+  }
+
+  // the brand check variables are declared in the module scope
+  let options = EmitOptions {
+    target: EsVersion::Es2021,
+    ..Default::default()
+  };
+  let (code, _, _) = transform("/app.ts", source, &options);
+  assert!(code.contains("\nvar _tmp, _brand_check_y = new WeakSet();\n"));
+  assert!(code.contains("try {\n    JSON.parse(\"\");\n}"));
+
+  // the async generators are lowered with the async functions
+  let source = "async function f() { await 1; }\nasync function* g() { for await (const x of g()) yield x; }";
+  for (target, expected) in [
+    (EsVersion::Es2018, "async function f() {"),
+    (EsVersion::Es2018, "async function* g() {\n    for await (const x of g())yield x;"),
+    (EsVersion::Es2017, "return _async_to_generator(function*() {"),
+    (EsVersion::Es2017, "return _wrap_async_generator(function*() {"),
+  ] {
+    let options = EmitOptions {
+      target,
+      ..Default::default()
+    };
+    let (code, _, _) = transform("/app.ts", source, &options);
+    assert!(code.contains(expected), "{:?}: {}", target, expected);
+  }
+}
+
+#[test]
+fn browser_targets() {
+  let source = r#"
+    class A {
+      #x = 1;
+      static { console.log("static"); }
+    }
+    let a: any = {};
+    a.b ||= 1;
+    console.log(a?.b ?? "b", 2 ** 3);
+  "#;
+  let transform = |target: &str| {
+    let options = SWCTransformOptions {
+      filename: "/app.ts".to_owned(),
+      code: source.as_bytes().to_vec(),
+      target: Some(target.to_owned()),
+      ..Default::default()
+    };
+    let output = crate::transform(&options).unwrap();
+    (String::from_utf8(output.code).unwrap(), output.lowered_features)
+  };
+
+  // safari 15 supports the class fields but not the static blocks
+  let (code, lowered) = transform("chrome >= 100, safari >= 15");
+  println!("{}", code);
+  assert!(code.contains("    #x = 1;\n"));
+  assert!(code.contains("static #_ = console.log(\"static\");"));
+  assert!(code.contains("a.b ||= 1;"));
+  assert_eq!(lowered, vec!["class-static-block"]);
+
+  let (code, lowered) = transform("chrome 80");
+  assert!(code.contains("_class_private_field_init(this, _x, {"));
+  assert!(code.contains("(_a = a).b || (_a.b = 1);"));
+  assert!(code.contains("a === null || a === void 0 ? void 0 : a.b"));
+  assert!(code.contains("2 ** 3"));
+  assert_eq!(
+    lowered,
+    vec![
+      "class-static-block",
+      "class-properties",
+      "logical-assignment-operators",
+      "optional-chaining"
+    ]
+  );
+
+  // the browsers on iOS use the WebKit of the system
+  let (_, lowered) = transform(
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 14_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/120.0.6099.119 Mobile/15E148 Safari/604.1",
+  );
+  assert_eq!(lowered, vec!["class-static-block", "class-properties"]);
+  let (_, lowered) =
+    transform("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36");
+  assert!(lowered.is_empty());
+  let (_, lowered) =
+    transform("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.1 Safari/605.1.15");
+  assert_eq!(lowered, vec!["class-static-block"]);
+
+  // chrome 91 supports the optional chaining
+  let (_, lowered) = transform("chrome > 90");
+  assert_eq!(lowered, vec!["class-static-block"]);
+  let (_, lowered) = transform("chrome > 90.0");
+  assert_eq!(lowered, vec!["class-static-block"]);
+  // safari 16.4 supports the static blocks
+  let (_, lowered) = transform("safari > 16.3");
+  assert!(lowered.is_empty());
+  let (_, lowered) = transform("safari > 16");
+  assert_eq!(lowered, vec!["class-static-block"]);
+
+  // the `not` queries are ignored and the parts of `and` narrow the browsers
+  let (_, lowered) = transform("chrome >= 100, not dead");
+  assert!(lowered.is_empty());
+  let (_, lowered) = transform("chrome >= 80 and chrome >= 90 and > 0.5%, safari >= 16.4 and safari < 17");
+  assert_eq!(lowered, vec!["class-static-block", "optional-chaining"]);
+
+  // the unsupported queries and the unknown User-Agents fall back to es2015
+  for target in ["last 2 versions", "defaults, chrome >= 100", "not dead", "curl/8.4.0"] {
+    let options = SWCTransformOptions {
+      filename: "/app.ts".to_owned(),
+      code: source.as_bytes().to_vec(),
+      target: Some(target.to_owned()),
+      ..Default::default()
+    };
+    let output = crate::transform(&options).unwrap();
+    assert!(output.lowered_features.contains(&"exponentiation-operator".to_owned()));
+    assert!(
+      output.warnings[0]
+        .message
+        .starts_with(&format!("Unsupported target \"{}\": ", target))
+    );
+    assert!(output.warnings[0].message.ends_with(", using \"es2015\""));
+  }
+}
+
+#[test]
+fn polyfills() {
+  let source = r#"
+    import { render } from "/render.ts";
+    const structuredClone = (v) => v;
+    const last = [1, 2, 3].findLast((n) => n > 1);
+    const { promise, resolve } = Promise.withResolvers();
+    render(structuredClone({ last }), Object.hasOwn({}, "a"), promise, resolve);
+  "#;
+  let transform = |target: &str| {
+    let options = SWCTransformOptions {
+      filename: "/app.ts".to_owned(),
+      code: source.as_bytes().to_vec(),
+      target: Some(target.to_owned()),
+      polyfills: Some(true),
+      import_map: Some(json!({
+        "imports": { "core-js/": "https://esm.sh/core-js@3/" }
+      })),
+      ..Default::default()
+    };
+    let output = crate::transform(&options).unwrap();
+    let code = String::from_utf8(output.code).unwrap();
+    let deps = output.deps.into_iter().map(|dep| dep.resolved_url).collect::<Vec<_>>();
+    (code, deps)
+  };
+
+  let (code, deps) = transform("es2021");
+  assert!(code.starts_with(
+    "import \"https://esm.sh/core-js@3/modules/es.promise.with-resolvers.js\";\nimport \"https://esm.sh/core-js@3/modules/es.array.find-last.js\";\nimport \"https://esm.sh/core-js@3/modules/es.object.has-own.js\";\nimport { render } from \"/render.ts\";\n"
+  ));
+  // the local `structuredClone` is not a polyfill
+  assert!(!code.contains("web.structured-clone"));
+  assert_eq!(
+    deps,
+    vec![
+      "https://esm.sh/core-js@3/modules/es.promise.with-resolvers.js",
+      "https://esm.sh/core-js@3/modules/es.array.find-last.js",
+      "https://esm.sh/core-js@3/modules/es.object.has-own.js",
+      "/render.ts",
+    ]
+  );
+
+  // chrome 100 supports `findLast` and `Object.hasOwn`
+  let (_, deps) = transform("chrome >= 100");
+  assert_eq!(
+    deps,
+    vec!["https://esm.sh/core-js@3/modules/es.promise.with-resolvers.js", "/render.ts"]
+  );
+
+  let (_, deps) = transform("esnext");
+  assert_eq!(deps, vec!["/render.ts"]);
+
+  // the methods are matched for the calls only, and the generic names for the literals only
+  let source = r#"
+    const { with: w, at } = options;
+    console.log(w, at, options.flat, list.at(0), map.with("a", 1), items?.flatMap((item) => item));
+    console.log([1, 2].at(-1), [1, 2].with(0, 3), "abc".at(-1));
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: source.as_bytes().to_vec(),
+    target: Some("es2018".to_owned()),
+    polyfills: Some(true),
+    ..Default::default()
+  };
+  let deps = crate::transform(&options)
+    .unwrap()
+    .deps
+    .into_iter()
+    .map(|dep| dep.specifier)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    deps,
+    vec![
+      "core-js/modules/es.array.with.js",
+      "core-js/modules/es.array.at.js",
+      "core-js/modules/es.string.at-alternative.js",
+      "core-js/modules/es.array.flat-map.js",
+    ]
+  );
+}
+
+#[test]
+fn external_helpers() {
+  let source = r#"
+    export class A {
+      #x = 1;
+    }
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: source.as_bytes().to_vec(),
+    target: Some("es2021".to_owned()),
+    import_map: Some(json!({
+      "imports": { "@swc/helpers/": "https://esm.sh/@swc/helpers@0.5/" }
+    })),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.starts_with("function _check_private_redeclaration(obj, privateCollection) {"));
+  assert!(output.deps.is_empty());
+
+  let options = SWCTransformOptions {
+    external_helpers: Some(true),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(
+    code.starts_with("import { _ as _class_private_field_init } from \"https://esm.sh/@swc/helpers@0.5/_/_class_private_field_init\";\n")
+  );
+  assert!(!code.contains("function _check_private_redeclaration"));
+  assert_eq!(output.deps.len(), 1);
+  assert_eq!(output.deps[0].specifier, "@swc/helpers/_/_class_private_field_init");
+  assert_eq!(
+    output.deps[0].resolved_url,
+    "https://esm.sh/@swc/helpers@0.5/_/_class_private_field_init"
+  );
+  assert!(output.warnings.is_empty());
+}
+
+#[test]
+fn minify() {
+  let source = r#"
+    import { useState } from "react";
+    function double(value: number) {
+      if (false) {
+        console.log("dead");
+      }
+      const doubled = value * 2;
+      return doubled;
+    }
+    export default function App() {
+      const [count, setCount] = useState(0);
+      return <button onClick={() => setCount(double(count))}>{count}</button>;
+    }
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/App.tsx".to_owned(),
+    code: source.as_bytes().to_vec(),
+    minify: Some(Minify::Bool(true)),
+    source_map: Some("external".to_owned()),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(!code.contains("dead"));
+  assert!(!code.contains("double"));
+  assert!(!code.contains("function App"));
+  assert!(code.contains("onClick:()=>o(2*e)"));
+  // the original names are kept in the source map
+  let map: serde_json::Value = serde_json::from_slice(&output.map.unwrap()).unwrap();
+  assert!(map["names"].as_array().unwrap().contains(&json!("count")));
+
+  let options = SWCTransformOptions {
+    minify: Some(Minify::Options(MinifyOptions {
+      compress: Some(false),
+      keep_fn_names: Some(true),
+      ..Default::default()
+    })),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("function double("));
+  assert!(code.contains("export default function App("));
+
+  // the component names are kept for the refresh runtime
+  let options = SWCTransformOptions {
+    minify: Some(Minify::Bool(true)),
+    dev: Some(DevOptions {
+      refresh: Some(RefreshOptions {
+        runtime: "/refresh.js".to_owned(),
+      }),
+      ..Default::default()
+    }),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("export default function App("));
+  assert!(code.contains("$RefreshReg$(App,\"App\")"));
+}
+
+#[test]
+fn define() {
+  let source = r#"
+    import { warn } from "./warn.ts";
+    if (process.env.NODE_ENV !== "production") {
+      warn("development build");
+    }
+    export const dev = __DEV__;
+    export const env = process.env["NODE_ENV"];
+    export function local(process: any) {
+      return process.env.NODE_ENV;
+    }
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: source.as_bytes().to_vec(),
+    define: Some(IndexMap::from([
+      ("process.env.NODE_ENV".to_owned(), "\"production\"".to_owned()),
+      ("__DEV__".to_owned(), "false".to_owned()),
+    ])),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("if (\"production\" !== \"production\") {"));
+  assert!(code.contains("export const dev = false;"));
+  assert!(code.contains("export const env = \"production\";"));
+  // the shadowed `process` is not replaced
+  assert!(code.contains("return process.env.NODE_ENV;"));
+
+  // the dead branch is removed by tree shaking
+  let options = SWCTransformOptions {
+    tree_shaking: Some(true),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(!code.contains("development build"));
+  assert!(!code.contains("import { warn }"));
+
+  let options = SWCTransformOptions {
+    define: Some(IndexMap::from([("__DEV__".to_owned(), "false;".to_owned())])),
+    ..options
+  };
+  let err = crate::transform(&options).err().unwrap();
+  assert_eq!(err.message, "Invalid define value of \"__DEV__\": false;");
+}
+
+#[test]
+fn import_meta_env() {
+  let source = r#"
+    if (import.meta.env.DEV) {
+      console.log(import.meta.env.MODE);
+    }
+    export const api = import.meta.env.API_URL;
+    export const debug = import.meta.env["DEBUG"];
+    export const missing = import.meta.env.MISSING;
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: source.as_bytes().to_vec(),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("if (false) {"));
+  assert!(code.contains("console.log(\"production\");"));
+  // the unknown variables are kept without the `env` option
+  assert!(code.contains("export const api = import.meta.env.API_URL;"));
+  assert!(output.warnings.is_empty());
+
+  let options = SWCTransformOptions {
+    env: Some(IndexMap::from([
+      ("API_URL".to_owned(), json!("https://api.example.com")),
+      ("DEBUG".to_owned(), json!(true)),
+    ])),
+    dev: Some(DevOptions::default()),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("if (true) {"));
+  assert!(code.contains("console.log(\"development\");"));
+  assert!(code.contains("export const api = \"https://api.example.com\";"));
+  assert!(code.contains("export const debug = true;"));
+  assert!(code.contains("export const missing = void 0;"));
+  assert_eq!(output.warnings.len(), 1);
+  assert_eq!(
+    output.warnings[0].to_string(),
+    "Unknown env variable \"import.meta.env.MISSING\", it's replaced with undefined at /app.ts:7:27"
+  );
+}
+
+#[test]
+fn used_exports() {
+  let source = r#"
+    import "./style.css";
+    import React from "react";
+    import { format } from "./format.ts";
+    import { parse } from "./parse.ts";
+    export const render = (v: string) => format(v), read = (v: string) => parse(v);
+    export function load() {
+      return import("./lazy.ts");
+    }
+    export default function App() {
+      return React.createElement("div");
+    }
+    export { check as validate } from "./check.ts";
+    export * from "./utils.ts";
+  "#;
+  let options = SWCTransformOptions {
+    filename: "/app.ts".to_owned(),
+    code: source.as_bytes().to_vec(),
+    used_exports: Some(UsedExports::Names(vec!["render".to_owned(), "validate".to_owned()])),
+    ..Default::default()
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("import \"./style.css?module\";"));
+  assert!(code.contains("import { format } from \"./format.ts\";"));
+  assert!(code.contains("export const render = (v)=>format(v);"));
+  assert!(code.contains("export { check as validate } from \"./check.ts\";"));
+  assert!(!code.contains("react"));
+  assert!(!code.contains("parse"));
+  assert!(!code.contains("lazy"));
+  assert!(!code.contains("App"));
+  // all the used exports are provided by the module itself
+  assert!(!code.contains("utils"));
+  let deps = output.deps.iter().map(|dep| dep.specifier.as_str()).collect::<Vec<_>>();
+  assert_eq!(deps, vec!["./style.css", "./format.ts", "./check.ts"]);
+
+  // the `export *` may provide the unknown names
+  let options = SWCTransformOptions {
+    used_exports: Some(UsedExports::Names(vec!["load".to_owned(), "debounce".to_owned()])),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert!(code.contains("export function load() {"));
+  assert!(code.contains("export * from \"./utils.ts\";"));
+  let deps = output.deps.iter().map(|dep| dep.specifier.as_str()).collect::<Vec<_>>();
+  assert_eq!(deps, vec!["./style.css", "./lazy.ts", "./utils.ts"]);
+
+  let options = SWCTransformOptions {
+    used_exports: Some(UsedExports::Keyword("none".to_owned())),
+    ..options
+  };
+  let output = crate::transform(&options).unwrap();
+  let code = String::from_utf8(output.code).unwrap();
+  assert_eq!(code, "import \"./style.css?module\";\n");
+  assert_eq!(output.deps.len(), 1);
+
+  let options = SWCTransformOptions {
+    used_exports: Some(UsedExports::Keyword("all".to_owned())),
+    ..options
+  };
+  assert_eq!(crate::transform(&options).err().unwrap().message, "Invalid usedExports: all");
+}
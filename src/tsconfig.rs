@@ -0,0 +1,70 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+/// The `compilerOptions` of a tsconfig.json or deno.json that affect the transform.
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOptions {
+  pub target: Option<String>,
+  pub jsx: Option<String>,
+  pub jsx_import_source: Option<String>,
+  pub jsx_factory: Option<String>,
+  pub jsx_fragment_factory: Option<String>,
+  pub jsx_precompile_skip_elements: Option<Vec<String>>,
+  pub experimental_decorators: Option<bool>,
+  pub emit_decorator_metadata: Option<bool>,
+  pub use_define_for_class_fields: Option<bool>,
+  pub verbatim_module_syntax: Option<bool>,
+  pub base_url: Option<String>,
+  pub paths: Option<IndexMap<String, Vec<String>>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TsConfigJson {
+  compiler_options: Option<CompilerOptions>,
+}
+
+/// A parsed tsconfig.json or deno.json, the other fields except the `compilerOptions` are ignored.
+pub struct TsConfig {
+  pub compiler_options: CompilerOptions,
+}
+
+impl TsConfig {
+  pub fn parse(tsconfig_raw: serde_json::Value) -> Result<Self, String> {
+    if !tsconfig_raw.is_object() {
+      return Err("Invalid tsconfig, must be an object".to_owned());
+    }
+    let tsconfig: TsConfigJson = serde_json::from_value(tsconfig_raw).map_err(|e| format!("Invalid tsconfig: {}", e))?;
+    Ok(TsConfig {
+      compiler_options: tsconfig.compiler_options.unwrap_or_default(),
+    })
+  }
+
+  /// Get the path aliases of the `paths` option, the targets are resolved to the absolute paths by the `baseUrl`.
+  /// The tsconfig is considered to be in the root directory, so `"./src/*"` is resolved to `"/src/*"`.
+  pub fn path_aliases(&self) -> Vec<(String, Vec<String>)> {
+    let Some(paths) = &self.compiler_options.paths else {
+      return vec![];
+    };
+    let base_url = self.compiler_options.base_url.as_deref().unwrap_or(".");
+    let base_url = "/".to_owned() + base_url.trim_start_matches("./").trim_start_matches('.').trim_matches('/');
+    paths
+      .iter()
+      .map(|(pattern, targets)| {
+        let targets = targets
+          .iter()
+          .map(|target| {
+            let target = target.trim_start_matches("./");
+            if target.starts_with('/') {
+              target.to_owned()
+            } else {
+              base_url.trim_end_matches('/').to_owned() + "/" + target
+            }
+          })
+          .collect();
+        (pattern.clone(), targets)
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,79 @@
+use crate::{CompiledImportMap, SWCTransformError, SWCTransformOptions, SWCTransformOutput, transform_module};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// The result of transforming a module in a batch, serialized as `{ result }` or `{ error }`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum SWCTransformManyResult {
+  Result(SWCTransformOutput),
+  Error(SWCTransformError),
+}
+
+/// Create a JS `Error` with the structured diagnostics attached as the `diagnostics` property.
+fn js_error(error: SWCTransformError) -> JsValue {
+  let js_error = js_sys::Error::new(&error.message);
+  if !error.diagnostics.is_empty() {
+    let _ = js_sys::Reflect::set(&js_error, &"diagnostics".into(), &to_js_value(&error.diagnostics));
+  }
+  js_error.into()
+}
+
+fn to_js_value<T: Serialize>(value: &T) -> JsValue {
+  value
+    .serialize(&serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true))
+    .unwrap()
+}
+
+#[wasm_bindgen(js_name = "transform")]
+pub fn transform(swc_transform_options: JsValue) -> Result<JsValue, JsValue> {
+  let options: SWCTransformOptions = serde_wasm_bindgen::from_value(swc_transform_options).expect("could not parse options");
+  let output = crate::transform(&options).map_err(js_error)?;
+  Ok(to_js_value(&output))
+}
+
+/// Transforms the module with a parsed import map, which avoids parsing the import map for every module.
+#[wasm_bindgen(js_name = "transformWithImportMap")]
+pub fn transform_with_import_map(swc_transform_options: JsValue, import_map: &CompiledImportMap) -> Result<JsValue, JsValue> {
+  let options: SWCTransformOptions = serde_wasm_bindgen::from_value(swc_transform_options).expect("could not parse options");
+  let output = crate::transform_with_import_map(&options, import_map).map_err(js_error)?;
+  Ok(to_js_value(&output))
+}
+
+/// Transforms many modules in one call, a failed module does not abort the batch.
+#[wasm_bindgen(js_name = "transformMany")]
+pub fn transform_many(swc_transform_options: JsValue) -> Result<JsValue, JsValue> {
+  transform_many_with(swc_transform_options, None)
+}
+
+/// Transforms many modules in one call with a parsed import map.
+#[wasm_bindgen(js_name = "transformManyWithImportMap")]
+pub fn transform_many_with_import_map(swc_transform_options: JsValue, import_map: &CompiledImportMap) -> Result<JsValue, JsValue> {
+  transform_many_with(swc_transform_options, Some(import_map))
+}
+
+fn transform_many_with(swc_transform_options: JsValue, import_map: Option<&CompiledImportMap>) -> Result<JsValue, JsValue> {
+  let Ok(inputs) = swc_transform_options.dyn_into::<js_sys::Array>() else {
+    return Err(JsError::new("The inputs must be an array").into());
+  };
+  // share the swc globals between the modules
+  let results = swc_common::GLOBALS.set(&swc_common::Globals::new(), || {
+    inputs
+      .iter()
+      .map(|input| {
+        let ret = match serde_wasm_bindgen::from_value::<SWCTransformOptions>(input) {
+          Ok(options) if options.import_map.is_some() => Err(SWCTransformError::new(
+            "The \"importMap\" option can not be used in a batch, pass the shared import map instead".to_owned(),
+          )),
+          Ok(options) => transform_module(&options, import_map),
+          Err(e) => Err(SWCTransformError::new(format!("Invalid options: {}", e))),
+        };
+        match ret {
+          Ok(output) => SWCTransformManyResult::Result(output),
+          Err(error) => SWCTransformManyResult::Error(error),
+        }
+      })
+      .collect::<Vec<_>>()
+  });
+  Ok(to_js_value(&results))
+}
@@ -1,6 +1,6 @@
 import { readFile } from "node:fs/promises";
 import { CompressionStream } from "node:stream/web";
-import init, { transform } from "./pkg/tsx.js";
+import init, { ImportMap, transform, transformManyWithImportMap, transformWithImportMap } from "./pkg/tsx.js";
 
 async function load() {
   const wasmData = await readFile(new URL("./pkg/tsx_bg.wasm", import.meta.url));
@@ -65,6 +65,10 @@ async function test() {
       console.log(code);
       throw new Error("source map not generated");
     }
+    if (!ret.deps.some((dep) => dep.specifier === "react-dom/client" && dep.kind === "static")) {
+      console.log(ret.deps);
+      throw new Error("deps not reported");
+    }
   }
 
   // use `jsxImportSource` option
@@ -112,6 +116,44 @@ async function test() {
     if (error.message !== "Expected '{', got 'ident' at /source.ts:1:7") {
       throw error;
     }
+    const [diagnostic] = error.diagnostics;
+    if (diagnostic.start.line !== 1 || diagnostic.start.column !== 7 || !diagnostic.codeFrame.includes("^^^")) {
+      console.log(error.diagnostics);
+      throw new Error("diagnostics not reported");
+    }
+  }
+
+  // use a parsed import map
+  {
+    const importMap = new ImportMap({
+      imports: {
+        "preact": "https://esm.sh/preact@10",
+        "preact/": "https://esm.sh/preact@10/",
+      },
+    });
+    const ret = transformWithImportMap({
+      filename: "/app.jsx",
+      code: enc.encode(`export default function App() { return <h1>Hello world!</h1> }`),
+    }, importMap);
+    const code = dec.decode(ret.code);
+    if (!code.includes(`import { jsx as _jsx } from "https://esm.sh/preact@10/jsx-runtime"`)) {
+      console.log(code);
+      throw new Error("jsx import source not detected from the parsed import map");
+    }
+
+    const results = transformManyWithImportMap([
+      { filename: "/a.ts", code: enc.encode(`import { h } from "preact"; export const a: number = 1`) },
+      { filename: "/b.ts", code: enc.encode(`export App() {}`) },
+    ], importMap);
+    if (!dec.decode(results[0].result.code).includes(`from "https://esm.sh/preact@10"`)) {
+      console.log(results);
+      throw new Error("batch transform failed");
+    }
+    if (!results[1].error || results[1].error.diagnostics.length !== 1) {
+      console.log(results);
+      throw new Error("batch transform error not reported");
+    }
+    importMap.free();
   }
 
   // use `lang` option
@@ -1,10 +1,40 @@
 /** Import Map fllow the spec: https://wicg.github.io/import-maps/ */
-export interface ImportMap {
+export interface ImportMapJSON {
   $src?: string;
   imports?: Record<string, string>;
   scopes?: Record<string, Record<string, string>>;
 }
 
+/** A tsconfig.json or deno.json. */
+export interface TsConfigJSON {
+  compilerOptions?: {
+    target?: string;
+    jsx?: "react" | "react-jsx" | "react-jsxdev" | "preserve" | "react-native" | "precompile";
+    jsxImportSource?: string;
+    jsxFactory?: string;
+    jsxFragmentFactory?: string;
+    jsxPrecompileSkipElements?: string[];
+    experimentalDecorators?: boolean;
+    emitDecoratorMetadata?: boolean;
+    useDefineForClassFields?: boolean;
+    verbatimModuleSyntax?: boolean;
+    baseUrl?: string;
+    paths?: Record<string, string[]>;
+    [key: string]: unknown;
+  };
+  [key: string]: unknown;
+}
+
+/** A parsed import map that can be shared by many `transform` calls. */
+export class ImportMap {
+  /** Parses the import map, throws an error if the import map is invalid. */
+  constructor(importMap: ImportMapJSON);
+  /** The non-fatal issues found while parsing the import map. */
+  readonly diagnostics: Diagnostic[];
+  /** Releases the memory of the import map. */
+  free(): void;
+}
+
 /** Delopment options. */
 export interface DevOptions {
   /**
@@ -32,20 +62,82 @@ export interface DevOptions {
   jsxSource?: { fileName: string };
 }
 
+/** Solid JSX options. */
+export interface SolidOptions {
+  /** The output mode, `"ssr"` generates the HTML strings for the server rendering, default is `"dom"`. */
+  generate?: "dom" | "ssr";
+  /** Delegate the events like `onClick` to the document, default is enabled. */
+  delegateEvents?: boolean;
+}
+
+/** Minify options, all of them are enabled by `minify: true` except the `keep*` options. */
+export interface MinifyOptions {
+  /** Drop the dead code and simplify the expressions, default is enabled. */
+  compress?: boolean;
+  /** Shorten the local names, default is enabled. */
+  mangle?: boolean;
+  /** Keep the class names, default is disabled. */
+  keepClassNames?: boolean;
+  /** Keep the function names, default is disabled. */
+  keepFnNames?: boolean;
+}
+
 /** Transform options. */
 export interface TransformOptions {
   /** The file name, used for source map and error message. */
   filename: string;
-  /** The code to transform. */
+  /** The code to transform, UTF-8 or UTF-16 with a BOM. */
   code: string | Uint8Array;
   /** The code language, default is using the file extension. */
   lang?: "ts" | "tsx" | "js" | "jsx";
-  /** The transform target, default is "esnext". */
-  target?: "es2015" | "es2016" | "es2017" | "es2018" | "es2019" | "es2020" | "es2021" | "es2022" | "es2023" | "es2024" | "esnext";
-  /** The import map, pass it if the browser does not support import maps. */
-  importMap?: ImportMap;
+  /**
+   * The transform target, the newer syntax is lowered to run in the target environment, default is "esnext".
+   * Besides the ECMAScript versions, a browserslist query like `"chrome >= 87, safari >= 14"` or a User-Agent string
+   * is accepted, only the syntax features that the browsers do not support are lowered.
+   * Only the version queries of the browsers are supported, e.g. `"firefox 78"`, `"safari > 14.1"` or `"ios_saf 14.0-14.4"`,
+   * combined with `,`, `or` and `and`, the `not` queries are ignored. The queries that need the usage data of caniuse,
+   * e.g. `"defaults"`, `"last 2 versions"` or `"> 0.5%"`, and the unknown User-Agents fall back to `"es2015"` with a warning.
+   */
+  target?:
+    | "es2015"
+    | "es2016"
+    | "es2017"
+    | "es2018"
+    | "es2019"
+    | "es2020"
+    | "es2021"
+    | "es2022"
+    | "es2023"
+    | "es2024"
+    | "esnext"
+    | (string & {});
+  /**
+   * Import the `core-js` polyfills of the APIs that are used by the module and not supported by the target, default is disabled.
+   * For example, `import "core-js/modules/es.array.find-last.js"` is added for the `arr.findLast()` call with the `"es2022"` target,
+   * the methods with the generic names, e.g. `at()` and `with()`, are only detected on the array and string literals,
+   * map the `core-js/` in the import map to load the polyfills, e.g. `{ "core-js/": "https://esm.sh/core-js@3/" }`.
+   */
+  polyfills?: boolean;
+  /**
+   * Import the helpers of the lowered syntax from `@swc/helpers` instead of inlining them into every module, default is disabled.
+   * The helper imports are resolved by the import map, e.g. `{ "@swc/helpers/": "https://esm.sh/@swc/helpers@0.5/" }`.
+   */
+  externalHelpers?: boolean;
+  /** The import map, pass it if the browser does not support import maps. Use a parsed `ImportMap` to share it between many modules. */
+  importMap?: ImportMapJSON | ImportMap;
+  /**
+   * The raw JSON of a tsconfig.json or deno.json, the following `compilerOptions` are supported:
+   * `target`, `jsx`, `jsxImportSource`, `jsxFactory`, `jsxFragmentFactory`, `jsxPrecompileSkipElements`, `experimentalDecorators`,
+   * `emitDecoratorMetadata`, `useDefineForClassFields`, `verbatimModuleSyntax`, `baseUrl` and `paths`.
+   *
+   * The transform options take precedence over the tsconfig, and the `paths` are resolved relative to the root directory.
+   */
+  tsconfig?: TsConfigJSON;
   /**
    * Specifies the JSX import source. if not specified, it will check the import specifier which ends with `/jsx-runtime` from the import map.
+   * The `@jsxImportSource`, `@jsx`, `@jsxFrag` and `@jsxRuntime` pragma comments in the file take precedence over this option.
+   * The JSX of `solid-js` and `vue` is compiled by their own transforms instead of the `jsx-runtime`,
+   * and the `defineComponent` exports of Vue are registered to the Vue HMR runtime in the `dev.hmr` mode.
    *
    * For example, the jsx import source will be `preact` with the following import map:
    *
@@ -58,14 +150,84 @@ export interface TransformOptions {
    * ```
    */
   jsxImportSource?: string;
-  /** minify outputed code, default is disabled. */
-  minify?: boolean;
+  /**
+   * The JSX runtime, default is `"automatic"`, or `"classic"` if the `jsxFactory` or `jsxFragmentFactory` is specified.
+   * - `"automatic"`: imports the `jsx` function from the `<jsxImportSource>/jsx-runtime`.
+   * - `"classic"`: calls the `jsxFactory`, e.g. `h("div", null)`.
+   * - `"preserve"`: keeps the JSX as is for the downstream JSX compilers, the TypeScript is still stripped.
+   * - `"precompile"`: serializes the static JSX to HTML string templates for the server rendering, like the `jsx: "precompile"` of Deno,
+   *   the `jsxTemplate`, `jsxAttr`, `jsxEscape` and `jsx` functions are imported from the `<jsxImportSource>/jsx-runtime`.
+   *   The `jsxImportSource` is required since `react` does not provide these functions, e.g. `"preact"`.
+   */
+  jsxRuntime?: "automatic" | "classic" | "preserve" | "precompile";
+  /** The elements that are created by the `jsx` function instead of being serialized by the `"precompile"` runtime, e.g. `["a", "img"]`. */
+  jsxPrecompileSkipElements?: string[];
+  /** The function of the classic JSX runtime to create elements, default is `"React.createElement"`. */
+  jsxFactory?: string;
+  /** The fragment of the classic JSX runtime, default is `"React.Fragment"`. */
+  jsxFragmentFactory?: string;
+  /**
+   * Options of the Solid JSX transform, which is used instead of the automatic runtime when the JSX import source is `solid-js`.
+   * The JSX is compiled to the `template()`, `insert()` and `effect()` calls imported from `solid-js/web`.
+   */
+  solid?: SolidOptions;
+  /**
+   * the decorators proposal to transform, default is `"legacy"` for TypeScript and `"2023-11"` for JavaScript.
+   * - `"legacy"`: the TypeScript `experimentalDecorators`.
+   * - `"2023-11"`: the TC39 standard decorators, supports `accessor` fields and `Symbol.metadata`,
+   *   they are transformed with the 2022-03 version of the proposal, so the later semantic changes,
+   *   e.g. the `addInitializer()` of the field decorators, are not applied.
+   * - `"none"`: keep the decorators as is.
+   */
+  decorators?: "legacy" | "2023-11" | "none";
+  /**
+   * emit the `design:type`, `design:paramtypes` and `design:returntype` metadata for the legacy decorators of TypeScript,
+   * default is disabled.
+   */
+  emitDecoratorMetadata?: boolean;
+  /**
+   * minify outputed code with the swc minifier, default is disabled.
+   * The dead code is dropped and the local names are mangled, the function and class names are kept in the refresh mode.
+   */
+  minify?: boolean | MinifyOptions;
+  /**
+   * Replace the global identifiers and member expressions with the expressions at compile time,
+   * e.g. `{ "process.env.NODE_ENV": "\"production\"", "__DEV__": "false" }`.
+   * The dead branches like `if (process.env.NODE_ENV !== "production")` are removed when `treeShaking` is enabled.
+   */
+  define?: Record<string, string>;
+  /**
+   * The variables of `import.meta.env`, the accesses like `import.meta.env.API_URL` are replaced with the literals.
+   * The built-in `DEV`, `PROD` and `MODE` follow the `dev` option, and the unknown variables are replaced with `undefined` with a warning.
+   */
+  env?: Record<string, string | number | boolean>;
   /** strip unused code, default is disabled. */
   treeShaking?: boolean;
+  /**
+   * The export names that are imported by the other modules, or `"none"` if the module is only imported for the side effects.
+   * The other exports are dropped with the code they leave, and the imports that become unused are removed from the code and the `deps`.
+   */
+  usedExports?: string[] | "none";
   /** create source map, default is disabled. */
   sourceMap?: "inline" | "external";
   /** development mode, default is disabled. */
   dev?: DevOptions;
+  /** throw the syntax errors that the parser recovered from instead of reporting them as warnings, default is disabled. */
+  failOnRecoveredErrors?: boolean;
+}
+
+/** A dependency of the transformed module. */
+export interface Dependency {
+  /** The original specifier in the source code. */
+  readonly specifier: string;
+  /** The resolved URL. */
+  readonly resolvedUrl: string;
+  /** The kind of the import/export statement. */
+  readonly kind: "static" | "dynamic" | "re-export" | "export-all";
+  /** The import attributes, e.g. `{ type: "json" }`. */
+  readonly attributes?: Record<string, string>;
+  /** The byte range of the import/export statement in the input bytes, e.g. the UTF-16 bytes with the BOM, not set for generated imports. */
+  readonly span?: { start: number; end: number };
 }
 
 /** Transform result. */
@@ -74,11 +236,54 @@ export interface TransformResult {
   readonly code: Uint8Array;
   /** The generated source map, if the `sourceMap` option is enabled as `external`. */
   readonly map?: Uint8Array;
+  /** The resolved dependencies of the module. */
+  readonly deps: Dependency[];
+  /** The non-fatal issues, e.g. recovered syntax errors, unresolved bare specifiers and invalid import map entries. */
+  readonly warnings: Diagnostic[];
+  /** The syntax features of the module that are lowered for the target, e.g. `"optional-chaining"`. */
+  readonly loweredFeatures: string[];
+  /** The resolved JSX runtime of the module, the JSX is kept in the code if it's `"preserve"`. */
+  readonly jsxRuntime: "automatic" | "classic" | "preserve" | "precompile";
+}
+
+/** A diagnostic message reported by the transformer. */
+export interface Diagnostic {
+  readonly message: string;
+  readonly severity: "error" | "warning" | "info";
+  readonly filename: string;
+  /** The start position, the line is 1-based and the column is 0-based. */
+  readonly start?: { line: number; column: number };
+  /** The end position, the line is 1-based and the column is 0-based. */
+  readonly end?: { line: number; column: number };
+  /** The byte range in the input bytes, e.g. the UTF-16 bytes with the BOM. */
+  readonly span?: { start: number; end: number };
+  /** The source line with a `^^^` marker under the span. */
+  readonly codeFrame?: string;
 }
 
-/** Transforms the given code. */
+/** The error thrown by `transform` when the code can not be parsed. */
+export interface TransformError extends Error {
+  readonly diagnostics?: Diagnostic[];
+}
+
+/** Transforms the given code, throws a `TransformError` if the code can not be parsed. */
 export function transform(options: TransformOptions): TransformResult;
 
+/** An input of `transformMany`. */
+export interface TransformInput {
+  filename: string;
+  code: string | Uint8Array;
+  options?: Omit<TransformOptions, "filename" | "code" | "importMap">;
+}
+
+/** The result of a module in `transformMany`, either `{ result }` or `{ error }`. */
+export type TransformManyResult =
+  | { readonly result: TransformResult; readonly error?: undefined }
+  | { readonly result?: undefined; readonly error: { readonly message: string; readonly diagnostics?: Diagnostic[] } };
+
+/** Transforms many modules in one call with a shared import map, a failed module does not abort the batch. */
+export function transformMany(inputs: TransformInput[], importMap?: ImportMapJSON | ImportMap): TransformManyResult[];
+
 /** Instantiates the given `module`, which can either be bytes or a precompiled `WebAssembly.Module`. */
 export function initSync(module: BufferSource | WebAssembly.Module): { memory: WebAssembly.Memory };
 